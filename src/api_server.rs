@@ -0,0 +1,359 @@
+use crate::wifi_share::parse_form_field;
+use iced::futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use iced::futures::lock::Mutex as AsyncMutex;
+use iced::futures::stream::{self, Stream, StreamExt};
+use serde_json::{json, Value};
+use std::io::Read;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+// How long a request waits for the app to answer; searches go out to YouTube
+const REPLY_TIMEOUT: Duration = Duration::from_secs(60);
+
+// History entries returned when the caller doesn't ask for a number
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+// Largest POST body read; requests only carry a few ids or a URL
+const MAX_REQUEST_BODY: u64 = 64 * 1024;
+
+// Authorised requests handled at once; each holds a thread while it waits for the app
+const MAX_IN_FLIGHT: usize = 8;
+
+// Numbers each server so a restarted one gets its own subscription
+static NEXT_SERVER_ID: AtomicU64 = AtomicU64::new(0);
+
+// What a caller asked for, handled in the app's update loop
+#[derive(Debug, Clone)]
+pub enum ApiCommand {
+    Search(String),
+    Enqueue {
+        input: String, // URL or search
+        filename: Option<String>, // used when a single video is queued
+    },
+    QueueStatus,
+    History(usize), // most recent entries to return
+    Share(Vec<String>), // video ids of downloaded files
+}
+
+// HTTP status and JSON body of an answer
+#[derive(Debug, Clone)]
+pub struct ApiResponse {
+    status: u16,
+    body: Value,
+}
+
+impl ApiResponse {
+    pub fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+    
+    pub fn accepted(body: Value) -> Self {
+        Self { status: 202, body }
+    }
+    
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+}
+
+// Where the answer to one request goes; its HTTP thread waits on the other end
+#[derive(Debug, Clone)]
+pub struct ApiReply(mpsc::Sender<ApiResponse>);
+
+impl ApiReply {
+    pub fn send(&self, response: ApiResponse) {
+        let _ = self.0.send(response);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiRequest {
+    pub command: ApiCommand,
+    pub reply: ApiReply,
+}
+
+// JSON API on localhost for scripts, bookmarklets and other tools
+pub struct ApiServer {
+    id: u64,
+    server: Arc<Server>,
+    token: Arc<Mutex<String>>,
+    requests: Arc<AsyncMutex<UnboundedReceiver<ApiRequest>>>,
+}
+
+impl ApiServer {
+    // Only listens on the loopback interface; every request must carry the token
+    pub fn start(port: u16, token: String) -> Result<Self, String> {
+        let server = Server::http(("127.0.0.1", port))
+            .map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
+        let server = Arc::new(server);
+        let token = Arc::new(Mutex::new(token));
+        let (sender, receiver) = unbounded();
+        
+        let listener = server.clone();
+        let listener_token = token.clone();
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        std::thread::spawn(move || {
+            for request in listener.incoming_requests() {
+                // Preflights and callers without the token are answered here, without a thread
+                if *request.method() == Method::Options {
+                    respond_preflight(request);
+                    continue;
+                }
+                let token = listener_token.lock().unwrap().clone();
+                if !token_matches(&given_token(&request), &token) {
+                    reply(request, ApiResponse::error(401, "Missing or wrong API token"));
+                    continue;
+                }
+                if in_flight.fetch_add(1, Ordering::SeqCst) >= MAX_IN_FLIGHT {
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    reply(request, ApiResponse::error(503, "Too many requests at once; try again shortly"));
+                    continue;
+                }
+                
+                let sender = sender.clone();
+                let in_flight = in_flight.clone();
+                // Searches take seconds, so each request waits for its answer on its own thread
+                std::thread::spawn(move || {
+                    handle(request, &sender);
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        
+        Ok(Self {
+            id: NEXT_SERVER_ID.fetch_add(1, Ordering::Relaxed),
+            server,
+            token,
+            requests: Arc::new(AsyncMutex::new(receiver)),
+        })
+    }
+    
+    pub fn set_token(&self, token: String) {
+        *self.token.lock().unwrap() = token;
+    }
+    
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+    
+    // Requests as they arrive, for the app's subscription to this server
+    pub fn requests(&self) -> impl Stream<Item = ApiRequest> + Send + 'static {
+        stream::unfold(self.requests.clone(), |requests| async move {
+            let request = requests.lock().await.next().await?;
+            Some((request, requests))
+        })
+    }
+}
+
+impl Drop for ApiServer {
+    // Ends the listener thread, which closes the socket once it lets go of the server
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+// A random 128-bit token as hex, from the operating system's secure random source
+pub fn generate_token() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|e| format!("Failed to generate an API token: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+// Bookmarklets call from the page they run on; the token, not the origin, is the guard
+fn respond_preflight(request: Request) {
+    let response = Response::empty(204)
+        .with_header(header("Access-Control-Allow-Methods", "GET, POST, OPTIONS"))
+        .with_header(header("Access-Control-Allow-Headers", "Authorization, Content-Type"));
+    respond(request, response);
+}
+
+// The bearer token, or the token query parameter for links that can't set headers
+fn given_token(request: &Request) -> String {
+    let bearer = request.headers().iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+        .map(|value| value.trim().to_string());
+    bearer
+        .or_else(|| {
+            let query = request.url().split_once('?').map(|(_, query)| query).unwrap_or("");
+            parse_form_field(query, "token")
+        })
+        .unwrap_or_default()
+}
+
+// Answer an authorised request, waiting for the app when it has to
+fn handle(mut request: Request, requests: &UnboundedSender<ApiRequest>) {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((url.as_str(), ""));
+    
+    let mut body = String::new();
+    if *request.method() == Method::Post {
+        let _ = request.as_reader().take(MAX_REQUEST_BODY).read_to_string(&mut body);
+    }
+    
+    let command = match parse_command(request.method(), path, query, &body) {
+        Ok(command) => command,
+        Err(response) => {
+            reply(request, response);
+            return;
+        }
+    };
+    
+    let (sender, receiver) = mpsc::channel();
+    let _ = requests.unbounded_send(ApiRequest {
+        command,
+        reply: ApiReply(sender),
+    });
+    
+    let response = receiver.recv_timeout(REPLY_TIMEOUT)
+        .unwrap_or_else(|_| ApiResponse::error(504, "Nightingale did not answer in time"));
+    reply(request, response);
+}
+
+fn parse_command(method: &Method, path: &str, query: &str, body: &str) -> Result<ApiCommand, ApiResponse> {
+    let json_body = || -> Result<Value, ApiResponse> {
+        serde_json::from_str(body).map_err(|e| ApiResponse::error(400, format!("Invalid JSON body: {}", e)))
+    };
+    
+    match (method, path.trim_end_matches('/')) {
+        (Method::Get, "/search") => {
+            let query = parse_form_field(query, "q")
+                .filter(|q| !q.trim().is_empty())
+                .ok_or_else(|| ApiResponse::error(400, "Missing search query: /search?q=..."))?;
+            Ok(ApiCommand::Search(query.trim().to_string()))
+        }
+        (Method::Post, "/enqueue") => {
+            let body = json_body()?;
+            let field = |name: &str| body[name].as_str().map(str::trim).filter(|v| !v.is_empty());
+            let input = match (field("video_id"), field("url"), field("query")) {
+                (Some(video_id), _, _) => crate::youtube_url::video_url(video_id),
+                (None, Some(url), _) => url.to_string(),
+                (None, None, Some(query)) => query.to_string(),
+                _ => return Err(ApiResponse::error(400, "Give a video_id, url or query to download")),
+            };
+            Ok(ApiCommand::Enqueue {
+                input,
+                filename: field("filename").map(str::to_string),
+            })
+        }
+        (Method::Get, "/queue") => Ok(ApiCommand::QueueStatus),
+        (Method::Get, "/history") => {
+            let limit = parse_form_field(query, "limit")
+                .and_then(|limit| limit.parse().ok())
+                .unwrap_or(DEFAULT_HISTORY_LIMIT);
+            Ok(ApiCommand::History(limit))
+        }
+        (Method::Post, "/share") => {
+            let body = json_body()?;
+            let video_ids: Vec<String> = body["video_ids"].as_array()
+                .map(|ids| ids.iter().filter_map(|id| id.as_str()).map(str::to_string).collect())
+                .unwrap_or_default();
+            if video_ids.is_empty() {
+                return Err(ApiResponse::error(400, "Give the video_ids of downloaded files to share"));
+            }
+            Ok(ApiCommand::Share(video_ids))
+        }
+        (_, "/search" | "/enqueue" | "/queue" | "/history" | "/share") => {
+            Err(ApiResponse::error(405, format!("{} is not supported here", method)))
+        }
+        _ => Err(ApiResponse::error(404, format!("No such endpoint: {}", path))),
+    }
+}
+
+// Compare without stopping at the first difference, so timing gives nothing away
+fn token_matches(given: &str, token: &str) -> bool {
+    !token.is_empty()
+        && given.len() == token.len()
+        && given.bytes().zip(token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+fn reply(request: Request, response: ApiResponse) {
+    let response = Response::from_string(response.body.to_string())
+        .with_status_code(response.status)
+        .with_header(header("Content-Type", "application/json"));
+    respond(request, response);
+}
+
+fn respond<R: std::io::Read>(request: Request, response: Response<R>) {
+    let _ = request.respond(response.with_header(header("Access-Control-Allow-Origin", "*")));
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field.as_bytes(), value.as_bytes()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn status(result: Result<ApiCommand, ApiResponse>) -> u16 {
+        result.err().map(|response| response.status).unwrap_or(0)
+    }
+    
+    #[test]
+    fn parses_searches_and_queries() {
+        let command = parse_command(&Method::Get, "/search", "q=lofi+beats%21", "").unwrap();
+        assert!(matches!(command, ApiCommand::Search(q) if q == "lofi beats!"));
+        assert_eq!(status(parse_command(&Method::Get, "/search", "q=%20", "")), 400);
+        
+        assert!(matches!(parse_command(&Method::Get, "/queue/", "", ""), Ok(ApiCommand::QueueStatus)));
+        assert!(matches!(parse_command(&Method::Get, "/history", "limit=5", ""), Ok(ApiCommand::History(5))));
+        assert!(matches!(
+            parse_command(&Method::Get, "/history", "limit=lots", ""),
+            Ok(ApiCommand::History(DEFAULT_HISTORY_LIMIT))
+        ));
+    }
+    
+    #[test]
+    fn parses_enqueue_bodies() {
+        let command = parse_command(&Method::Post, "/enqueue", "", r#"{"video_id": "dQw4w9WgXcQ", "url": "ignored"}"#).unwrap();
+        assert!(matches!(
+            command,
+            ApiCommand::Enqueue { input, filename: None } if input == "https://www.youtube.com/watch?v=dQw4w9WgXcQ"
+        ));
+        
+        let command = parse_command(&Method::Post, "/enqueue", "", r#"{"query": " lofi ", "filename": "Study"}"#).unwrap();
+        assert!(matches!(
+            command,
+            ApiCommand::Enqueue { input, filename: Some(filename) } if input == "lofi" && filename == "Study"
+        ));
+        
+        assert_eq!(status(parse_command(&Method::Post, "/enqueue", "", "{}")), 400);
+        assert_eq!(status(parse_command(&Method::Post, "/enqueue", "", "not json")), 400);
+    }
+    
+    #[test]
+    fn parses_share_bodies() {
+        let command = parse_command(&Method::Post, "/share", "", r#"{"video_ids": ["a", 1, "b"]}"#).unwrap();
+        assert!(matches!(command, ApiCommand::Share(ids) if ids == ["a", "b"]));
+        assert_eq!(status(parse_command(&Method::Post, "/share", "", r#"{"video_ids": []}"#)), 400);
+    }
+    
+    #[test]
+    fn rejects_unknown_routes() {
+        assert_eq!(status(parse_command(&Method::Post, "/search", "q=x", "")), 405);
+        assert_eq!(status(parse_command(&Method::Get, "/enqueue", "", "")), 405);
+        assert_eq!(status(parse_command(&Method::Get, "/", "", "")), 404);
+    }
+    
+    #[test]
+    fn matches_tokens_exactly() {
+        assert!(token_matches("0123abcd", "0123abcd"));
+        assert!(!token_matches("0123abce", "0123abcd"));
+        assert!(!token_matches("0123abc", "0123abcd"));
+        assert!(!token_matches("", ""));
+    }
+    
+    #[test]
+    fn generates_distinct_tokens() {
+        let token = generate_token().unwrap();
+        assert_eq!(token.len(), 32);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, generate_token().unwrap());
+    }
+}
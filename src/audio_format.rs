@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// Format yt-dlp extracts the audio to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFormat {
+    #[default]
+    Mp3,
+    M4a,
+    Opus,
+    Flac,
+}
+
+impl AudioFormat {
+    pub const ALL: [AudioFormat; 4] = [
+        AudioFormat::Mp3,
+        AudioFormat::M4a,
+        AudioFormat::Opus,
+        AudioFormat::Flac,
+    ];
+    
+    // yt-dlp --audio-format value, which is also the file extension
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::M4a => "m4a",
+            AudioFormat::Opus => "opus",
+            AudioFormat::Flac => "flac",
+        }
+    }
+    
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path.extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        Self::ALL.into_iter()
+            .find(|format| format.extension() == extension)
+            .unwrap_or_default()
+    }
+    
+    // ffmpeg arguments to re-encode audio in this format after filtering it
+    pub fn encoder_args(&self) -> &'static [&'static str] {
+        match self {
+            AudioFormat::Mp3 => &["-c:a", "libmp3lame", "-q:a", "2"],
+            AudioFormat::M4a => &["-c:a", "aac", "-b:a", "256k"],
+            AudioFormat::Opus => &["-c:a", "libopus", "-b:a", "160k"],
+            AudioFormat::Flac => &["-c:a", "flac"],
+        }
+    }
+    
+    // ffmpeg muxer arguments that keep tags readable everywhere; ID3v2.4 confuses some players
+    pub fn tag_args(&self) -> &'static [&'static str] {
+        match self {
+            AudioFormat::Mp3 => &["-id3v2_version", "3"],
+            _ => &[],
+        }
+    }
+}
+
+impl std::fmt::Display for AudioFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AudioFormat::Mp3 => "MP3",
+            AudioFormat::M4a => "M4A (AAC)",
+            AudioFormat::Opus => "Opus",
+            AudioFormat::Flac => "FLAC",
+        };
+        write!(f, "{}", name)
+    }
+}
@@ -0,0 +1,265 @@
+use crate::audio_format::AudioFormat;
+use crate::youtube_url::{self, YouTubeLink};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Channel tabs whose entries are videos; /playlists and /releases list playlists instead
+const VIDEO_TABS: &[&str] = &["videos", "streams", "shorts"];
+
+// Shortest time between two listings of the same subscription
+pub const MIN_CHECK_MINS: u64 = 15;
+
+// A playlist or channel re-listed periodically for new uploads
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelSubscription {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub output_dir: Option<PathBuf>, // download folder, or the default one when unset
+    #[serde(default)]
+    pub format: AudioFormat,
+    #[serde(default)]
+    pub auto_download: bool, // queue new items without asking
+    // Only read from configs saved before seen items moved to their own file
+    #[serde(default, skip_serializing)]
+    pub seen: Vec<String>,
+    #[serde(default)]
+    pub last_checked: Option<u64>, // seconds since the Unix epoch
+    #[serde(default)]
+    pub last_failed: Option<u64>, // so a broken listing waits like a checked one
+}
+
+impl ChannelSubscription {
+    pub fn new(url: &str) -> Result<Self, String> {
+        // A link to a video in a playlist subscribes to the playlist
+        let (url, name) = match YouTubeLink::parse(url) {
+            YouTubeLink::Playlist { list } | YouTubeLink::VideoInPlaylist { list, .. } => {
+                (youtube_url::playlist_url(&list), format!("Playlist {}", list))
+            }
+            YouTubeLink::Channel { url } => {
+                let url = url.trim_end_matches('/');
+                let (channel, tab) = split_channel_tab(url);
+                if let Some(tab) = tab.filter(|tab| !VIDEO_TABS.contains(tab)) {
+                    return Err(format!(
+                        "The {} tab doesn't list videos; subscribe to the channel or one of its playlists",
+                        tab
+                    ));
+                }
+                let name = channel.rsplit('/').next().unwrap_or(channel).to_string();
+                (url.to_string(), name)
+            }
+            _ => return Err("Enter a YouTube playlist or channel URL".to_string()),
+        };
+        
+        Ok(Self {
+            name,
+            url,
+            output_dir: None,
+            format: AudioFormat::default(),
+            auto_download: false,
+            seen: Vec::new(),
+            last_checked: None,
+            last_failed: None,
+        })
+    }
+    
+    // A bare channel URL lists its tabs rather than its uploads, so point it at the videos
+    // tab; so are subscriptions saved on a playlists tab before those were turned away
+    pub fn listing_url(&self) -> String {
+        let url = self.url.trim_end_matches('/');
+        if !is_channel(url) {
+            return url.to_string();
+        }
+        
+        match split_channel_tab(url) {
+            (_, Some(tab)) if VIDEO_TABS.contains(&tab) => url.to_string(),
+            (channel, _) => format!("{}/videos", channel),
+        }
+    }
+    
+    pub fn is_due(&self, interval_mins: u64) -> bool {
+        // A failed listing doesn't count as the first check, but is still retried on the interval
+        self.last_checked.max(self.last_failed).is_none_or(|checked| {
+            now_secs().saturating_sub(checked) >= interval_mins.max(MIN_CHECK_MINS) * 60
+        })
+    }
+    
+    // The first listing only records what is already there, so subscribing to a channel
+    // doesn't queue its whole back catalogue
+    pub fn is_first_check(&self) -> bool {
+        self.last_checked.is_none()
+    }
+}
+
+fn seen_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("nightingale").join("subscriptions_seen.json"))
+}
+
+// Video ids each subscription has listed before, queued or dismissed; kept out of
+// config.json, which is rewritten on every settings change
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeenItems {
+    items: HashMap<String, Vec<String>>, // subscription url to video ids
+}
+
+impl SeenItems {
+    pub fn load() -> Self {
+        seen_file()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+    
+    pub fn save(&self) -> Result<(), String> {
+        let path = seen_file().ok_or_else(|| "Could not find config directory".to_string())?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        
+        let contents = serde_json::to_string(self)
+            .map_err(|e| format!("Failed to serialize seen items: {}", e))?;
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to write seen items: {}", e))
+    }
+    
+    // Move ids out of subscriptions loaded from an older config; true if there were any
+    pub fn take_legacy(&mut self, subscriptions: &mut [ChannelSubscription]) -> bool {
+        let mut moved = false;
+        for subscription in subscriptions {
+            for video_id in std::mem::take(&mut subscription.seen) {
+                self.mark(&subscription.url, &video_id);
+                moved = true;
+            }
+        }
+        moved
+    }
+    
+    pub fn contains(&self, url: &str, video_id: &str) -> bool {
+        self.items.get(url).is_some_and(|ids| ids.iter().any(|id| id == video_id))
+    }
+    
+    pub fn mark(&mut self, url: &str, video_id: &str) {
+        let ids = self.items.entry(url.to_string()).or_default();
+        if !ids.iter().any(|id| id == video_id) {
+            ids.push(video_id.to_string());
+        }
+    }
+    
+    // Forget ids the latest listing no longer has, so the list stays the size of the
+    // listing; an item that has dropped off won't be listed as new again
+    pub fn retain_listed(&mut self, url: &str, listed: &HashSet<&str>) {
+        if let Some(ids) = self.items.get_mut(url) {
+            ids.retain(|id| listed.contains(id.as_str()));
+        }
+    }
+    
+    pub fn remove(&mut self, url: &str) {
+        self.items.remove(url);
+    }
+}
+
+fn is_channel(url: &str) -> bool {
+    ["/@", "/channel/", "/c/", "/user/"].iter().any(|pattern| url.contains(pattern))
+}
+
+// The channel part of a channel URL and the tab after it, as in ("…/@name", Some("videos"))
+fn split_channel_tab(url: &str) -> (&str, Option<&str>) {
+    let path_start = url.find("youtube.com/").map(|i| i + "youtube.com/".len()).unwrap_or(0);
+    let path = &url[path_start..];
+    
+    // @handle is one segment; channel/<id>, c/<name> and user/<name> are two
+    let channel_segments = if path.starts_with('@') { 1 } else { 2 };
+    let parts: Vec<&str> = path.splitn(channel_segments + 1, '/').collect();
+    let channel_len = parts.iter().take(channel_segments).map(|part| part.len() + 1).sum::<usize>() - 1;
+    let tab = parts.get(channel_segments)
+        .map(|rest| rest.split('/').next().unwrap_or(rest))
+        .filter(|tab| !tab.is_empty());
+    
+    (&url[..path_start + channel_len.min(path.len())], tab)
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn subscribes_to_playlists() {
+        let subscription = ChannelSubscription::new("https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PLx0sYbCqOb8TBPRdmBHs5Iftvv9TPboYG").unwrap();
+        assert_eq!(subscription.url, "https://www.youtube.com/playlist?list=PLx0sYbCqOb8TBPRdmBHs5Iftvv9TPboYG");
+        assert_eq!(subscription.name, "Playlist PLx0sYbCqOb8TBPRdmBHs5Iftvv9TPboYG");
+        assert_eq!(subscription.listing_url(), subscription.url);
+    }
+    
+    #[test]
+    fn subscribes_to_channels_and_video_tabs() {
+        let subscription = ChannelSubscription::new("youtube.com/@LofiGirl").unwrap();
+        assert_eq!(subscription.name, "@LofiGirl");
+        assert_eq!(subscription.listing_url(), "https://www.youtube.com/@LofiGirl/videos");
+        
+        let subscription = ChannelSubscription::new("https://www.youtube.com/channel/UCSJ4gkVC6NrvII8umztf0Ow/streams/").unwrap();
+        assert_eq!(subscription.name, "UCSJ4gkVC6NrvII8umztf0Ow");
+        assert_eq!(subscription.listing_url(), "https://www.youtube.com/channel/UCSJ4gkVC6NrvII8umztf0Ow/streams");
+        
+        let subscription = ChannelSubscription::new("https://m.youtube.com/c/Vevo/shorts").unwrap();
+        assert_eq!(subscription.name, "Vevo");
+    }
+    
+    #[test]
+    fn rejects_tabs_that_list_playlists() {
+        assert!(ChannelSubscription::new("https://www.youtube.com/@LofiGirl/playlists").is_err());
+        assert!(ChannelSubscription::new("https://www.youtube.com/channel/UCSJ4gkVC6NrvII8umztf0Ow/releases").is_err());
+        assert!(ChannelSubscription::new("https://www.youtube.com/watch?v=dQw4w9WgXcQ").is_err());
+        assert!(ChannelSubscription::new("lofi beats").is_err());
+        
+        // Saved before those tabs were turned away
+        let subscription = ChannelSubscription {
+            url: "https://www.youtube.com/@LofiGirl/playlists".to_string(),
+            ..ChannelSubscription::new("https://www.youtube.com/@LofiGirl").unwrap()
+        };
+        assert_eq!(subscription.listing_url(), "https://www.youtube.com/@LofiGirl/videos");
+    }
+    
+    #[test]
+    fn waits_at_least_the_minimum_interval() {
+        let mut subscription = ChannelSubscription::new("https://www.youtube.com/@LofiGirl").unwrap();
+        assert!(subscription.is_due(0));
+        
+        subscription.last_failed = Some(now_secs() - 5 * 60);
+        assert!(!subscription.is_due(0));
+        assert!(subscription.is_first_check());
+        
+        subscription.last_failed = None;
+        subscription.last_checked = Some(now_secs() - 5 * 60);
+        assert!(!subscription.is_due(0));
+        assert!(!subscription.is_due(MIN_CHECK_MINS));
+        
+        subscription.last_checked = Some(now_secs() - MIN_CHECK_MINS * 60);
+        assert!(subscription.is_due(0));
+        assert!(!subscription.is_due(60));
+    }
+    
+    #[test]
+    fn forgets_items_no_longer_listed() {
+        let url = "https://www.youtube.com/@LofiGirl";
+        let mut seen = SeenItems::default();
+        seen.mark(url, "aaaaaaaaaaa");
+        seen.mark(url, "bbbbbbbbbbb");
+        seen.mark(url, "aaaaaaaaaaa");
+        
+        seen.retain_listed(url, &HashSet::from(["bbbbbbbbbbb", "ccccccccccc"]));
+        assert!(!seen.contains(url, "aaaaaaaaaaa"));
+        assert!(seen.contains(url, "bbbbbbbbbbb"));
+        assert!(!seen.contains("https://www.youtube.com/@Other", "bbbbbbbbbbb"));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::audio_format::AudioFormat;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+// A chapter from yt-dlp's video info, times in seconds
+#[derive(Debug, Clone, Deserialize)]
+pub struct Chapter {
+    pub title: String,
+    pub start_time: f64,
+    pub end_time: f64,
+}
+
+// Read the chapter list from the full video info; videos without chapters give an empty list
+pub async fn fetch(video_id: String, cookie_args: Vec<String>) -> Result<Vec<Chapter>, String> {
+    let output = tokio::process::Command::new(crate::find_ytdlp())
+        .arg("--dump-single-json")
+        .arg("--skip-download")
+        .arg("--no-playlist")
+        .args(&cookie_args)
+        .arg(format!("https://www.youtube.com/watch?v={}", video_id))
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run yt-dlp: {}", e))?;
+    
+    if !output.status.success() {
+        return Err(format!("Failed to get chapters: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse video info: {}", e))?;
+    
+    match info.get("chapters") {
+        Some(chapters) if !chapters.is_null() => serde_json::from_value(chapters.clone())
+            .map_err(|e| format!("Failed to parse chapters: {}", e)),
+        _ => Ok(Vec::new()),
+    }
+}
+
+// Cut the file into one file per chapter in a folder named after it, tagged with
+// track numbers and chapter titles; the full file is left in place
+pub async fn split(ffmpeg: PathBuf, file: PathBuf, chapters: Vec<Chapter>, album: String) -> Result<Vec<PathBuf>, String> {
+    let stem = file.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid file name: {}", file.display()))?;
+    let folder = file.parent().unwrap_or(Path::new(".")).join(&stem);
+    std::fs::create_dir_all(&folder)
+        .map_err(|e| format!("Failed to create {}: {}", folder.display(), e))?;
+    
+    let format = AudioFormat::from_path(&file);
+    let total = chapters.len();
+    let mut tracks = Vec::with_capacity(total);
+    
+    for (index, chapter) in chapters.iter().enumerate() {
+        let number = index + 1;
+        let title = crate::clean_filename(&chapter.title);
+        let title = if title.is_empty() { format!("Track {}", number) } else { title };
+        let track_path = folder.join(format!("{:02} - {}.{}", number, title, format.extension()));
+        
+        // Stream copy keeps the audio untouched; audio frames are short enough to cut on
+        let output = tokio::process::Command::new(&ffmpeg)
+            .arg("-y")
+            .arg("-v").arg("error")
+            .arg("-i").arg(&file)
+            .arg("-ss").arg(format!("{:.3}", chapter.start_time))
+            .arg("-to").arg(format!("{:.3}", chapter.end_time))
+            .arg("-map").arg("0")
+            .arg("-map_metadata").arg("0")
+            .arg("-c").arg("copy")
+            .arg("-metadata").arg(format!("title={}", chapter.title))
+            .arg("-metadata").arg(format!("track={}/{}", number, total))
+            .arg("-metadata").arg(format!("album={}", album))
+            .args(format.tag_args())
+            .arg(&track_path)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+        
+        if !output.status.success() {
+            return Err(format!(
+                "ffmpeg failed on chapter {} ({}): {}",
+                number,
+                chapter.title,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        tracks.push(track_path);
+    }
+    
+    Ok(tracks)
+}
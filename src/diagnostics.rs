@@ -0,0 +1,260 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::{ffmpeg_install, ytdlp_update};
+
+// Free space below which the download directory check fails
+const MIN_FREE_SPACE_MB: u64 = 500;
+
+// Result of a single preflight check
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub passed: bool,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            passed: true,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+    
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            passed: false,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+// Everything the checks need to know about the current setup
+#[derive(Debug, Clone)]
+pub struct Environment {
+    pub ytdlp: String,
+    pub ffmpeg_location: Option<String>,
+    pub download_directory: Option<PathBuf>,
+}
+
+pub async fn run_checks(env: Environment) -> Vec<Check> {
+    let mut checks = Vec::new();
+    
+    checks.extend(check_ytdlp(&env.ytdlp).await);
+    checks.push(check_ffmpeg(env.ffmpeg_location.as_deref()).await);
+    checks.push(check_download_directory(env.download_directory.as_deref()).await);
+    checks.push(check_network().await);
+    
+    checks
+}
+
+// Plain-text report suitable for pasting into a bug ticket
+pub fn format_report(checks: &[Check]) -> String {
+    let mut report = format!(
+        "Nightingale {} diagnostics ({} {})\n\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+    );
+    
+    for check in checks {
+        let status = if check.passed { "PASS" } else { "FAIL" };
+        report.push_str(&format!("[{}] {}: {}\n", status, check.name, check.detail));
+        if let Some(hint) = &check.hint {
+            report.push_str(&format!("       Fix: {}\n", hint));
+        }
+    }
+    
+    report
+}
+
+async fn check_ytdlp(ytdlp: &str) -> Vec<Check> {
+    // find_ytdlp falls back to a bare "yt-dlp" when it should be resolved from PATH
+    let path = if Path::new(ytdlp).is_absolute() {
+        Some(PathBuf::from(ytdlp))
+    } else {
+        which(ytdlp)
+    };
+    
+    let Some(path) = path.filter(|p| p.exists()) else {
+        return vec![Check::fail(
+            "yt-dlp present",
+            format!("{} not found", ytdlp),
+            "Click \"Install yt-dlp\" in Settings, or install it with your package manager",
+        )];
+    };
+    
+    let mut checks = vec![Check::pass("yt-dlp present", path.display().to_string())];
+    
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let executable = path.metadata()
+            .map(|m| m.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false);
+        
+        checks.push(if executable {
+            Check::pass("yt-dlp executable", "Executable permission set")
+        } else {
+            Check::fail(
+                "yt-dlp executable",
+                "Missing executable permission",
+                format!("Run: chmod +x {}", path.display()),
+            )
+        });
+    }
+    
+    checks.push(check_shebang(&path));
+    
+    checks.push(match ytdlp_update::installed_version(ytdlp.to_string()).await {
+        Some(version) => Check::pass("yt-dlp version", version),
+        None => Check::fail(
+            "yt-dlp version",
+            "yt-dlp --version did not run",
+            "Reinstall yt-dlp from Settings, or roll back if this started after an update",
+        ),
+    });
+    
+    checks
+}
+
+// The zipapp build of yt-dlp is a Python script; its interpreter must exist
+fn check_shebang(path: &Path) -> Check {
+    let name = "python3 shebang";
+    
+    let Ok(bytes) = std::fs::read(path) else {
+        return Check::fail(name, "Could not read yt-dlp", "Check the file permissions of yt-dlp");
+    };
+    
+    if !bytes.starts_with(b"#!") {
+        return Check::pass(name, "Standalone binary, no Python needed");
+    }
+    
+    let first_line = String::from_utf8_lossy(&bytes[2..])
+        .lines()
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_string();
+    
+    let mut parts = first_line.split_whitespace();
+    let interpreter = match (parts.next(), parts.next()) {
+        (Some("/usr/bin/env"), Some(program)) => which(program),
+        (Some(program), _) => Some(PathBuf::from(program)).filter(|p| p.exists()),
+        _ => None,
+    };
+    
+    match interpreter {
+        Some(interpreter) => Check::pass(name, format!("#!{} ({})", first_line, interpreter.display())),
+        None => Check::fail(
+            name,
+            format!("Interpreter not found: #!{}", first_line),
+            "Install python3, then reinstall yt-dlp from Settings to rewrite the shebang",
+        ),
+    }
+}
+
+async fn check_ffmpeg(location: Option<&str>) -> Check {
+    let name = "ffmpeg present";
+    
+    let Some(location) = location else {
+        return Check::fail(
+            name,
+            "ffmpeg not found",
+            "Click \"Install ffmpeg\" in Settings, or choose an existing ffmpeg binary",
+        );
+    };
+    
+    let (ffmpeg, _) = ffmpeg_install::binaries(Path::new(location));
+    match ffmpeg_install::tool_version(ffmpeg.clone()).await {
+        Some(version) => Check::pass(name, format!("{} ({})", version, ffmpeg.display())),
+        None => Check::fail(
+            name,
+            format!("{} did not run", ffmpeg.display()),
+            "Reinstall ffmpeg from Settings, or choose a working ffmpeg binary",
+        ),
+    }
+}
+
+async fn check_download_directory(dir: Option<&Path>) -> Check {
+    let name = "Download directory";
+    
+    let Some(dir) = dir else {
+        return Check::fail(name, "Not set", "Choose a download directory in Settings");
+    };
+    
+    if !dir.is_dir() {
+        return Check::fail(
+            name,
+            format!("{} does not exist", dir.display()),
+            "Choose an existing download directory in Settings",
+        );
+    }
+    
+    let probe = dir.join(".nightingale-write-test");
+    if let Err(e) = std::fs::write(&probe, b"") {
+        return Check::fail(
+            name,
+            format!("{} is not writable: {}", dir.display(), e),
+            "Fix the folder permissions or choose another download directory",
+        );
+    }
+    let _ = std::fs::remove_file(&probe);
+    
+    match free_space_mb(dir).await {
+        Some(free) if free < MIN_FREE_SPACE_MB => Check::fail(
+            name,
+            format!("{} is writable but only {} MB free", dir.display(), free),
+            "Free up disk space or choose a download directory on another drive",
+        ),
+        Some(free) => Check::pass(name, format!("{} is writable, {} MB free", dir.display(), free)),
+        None => Check::pass(name, format!("{} is writable (free space unknown)", dir.display())),
+    }
+}
+
+async fn check_network() -> Check {
+    let name = "Network";
+    
+    let client = match reqwest::Client::builder().timeout(Duration::from_secs(10)).build() {
+        Ok(client) => client,
+        Err(e) => return Check::fail(name, format!("Failed to create client: {}", e), "Restart Nightingale"),
+    };
+    
+    match client.head("https://www.youtube.com").send().await {
+        Ok(response) => Check::pass(name, format!("youtube.com reachable (HTTP {})", response.status().as_u16())),
+        Err(e) => Check::fail(
+            name,
+            format!("youtube.com unreachable: {}", e),
+            "Check your internet connection, proxy or firewall",
+        ),
+    }
+}
+
+// Free space in megabytes on the filesystem holding dir, from `df`
+async fn free_space_mb(dir: &Path) -> Option<u64> {
+    let output = tokio::process::Command::new("df")
+        .arg("-Pk")
+        .arg(dir)
+        .output()
+        .await
+        .ok()?;
+    
+    // Second line: Filesystem 1024-blocks Used Available Capacity Mounted-on
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let available_kb: u64 = stdout.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()?;
+    Some(available_kb / 1024)
+}
+
+// Resolve a program name against PATH
+fn which(program: &str) -> Option<PathBuf> {
+    let path_var = std::env::var_os("PATH")?;
+    std::env::split_paths(&path_var)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
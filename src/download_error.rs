@@ -0,0 +1,286 @@
+// Known ways a yt-dlp download can fail, recognised from its stderr
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownloadError {
+    VideoUnavailable,
+    Private,
+    GeoBlocked,
+    AgeRestricted,
+    Throttled(u16), // HTTP status code (403 or 429)
+    Network, // connection failed, timed out or the name didn't resolve
+    FfmpegMissing,
+    DiskFull,
+    ExtractorOutdated,
+    FormatUnavailable, // the player client offered no usable format
+    Other(String),
+}
+
+// Follow-up offered next to a failed download
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SuggestedAction {
+    UpdateYtDlp,
+    EnableCookies,
+    InstallFfmpeg,
+    RetryLater,
+}
+
+impl SuggestedAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SuggestedAction::UpdateYtDlp => "Update yt-dlp",
+            SuggestedAction::EnableCookies => "Enable Cookies",
+            SuggestedAction::InstallFfmpeg => "Install ffmpeg",
+            SuggestedAction::RetryLater => "Retry",
+        }
+    }
+}
+
+impl DownloadError {
+    // Work out why yt-dlp failed from its stderr output. ERROR lines are read first and
+    // WARNING lines only when none of them names a cause; yt-dlp's own [...] lines are
+    // skipped, since they echo titles and filenames (e.g. [debug] Command-line config)
+    pub fn classify<'a>(lines: impl IntoIterator<Item = &'a str>, exit_code: Option<i32>) -> Self {
+        let lines: Vec<&str> = lines.into_iter()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('['))
+            .collect();
+        
+        // Search errors arrive wrapped, as in "Failed to get video info: ERROR: ..."
+        let errors: Vec<&str> = lines.iter()
+            .filter_map(|line| line.split_once("ERROR: ").map(|(_, message)| message))
+            .collect();
+        
+        if let Some(error) = errors.iter().find_map(|line| Self::cause(line)) {
+            return error;
+        }
+        
+        if let Some(error) = lines.iter()
+            .filter(|line| !line.contains("ERROR: "))
+            .find_map(|line| Self::cause(line))
+        {
+            return error;
+        }
+        
+        DownloadError::Other(errors.last().map(|message| message.to_string()).unwrap_or_else(|| {
+            format!("yt-dlp failed with exit code: {:?}. Check logs for details.", exit_code)
+        }))
+    }
+    
+    // The specific failure a single line of output describes, if any
+    fn cause(line: &str) -> Option<Self> {
+        let lower = line.to_lowercase();
+        
+        if lower.contains("private video") {
+            Some(DownloadError::Private)
+        } else if lower.contains("sign in to confirm your age")
+            || lower.contains("age-restricted")
+            || lower.contains("inappropriate for some users")
+        {
+            Some(DownloadError::AgeRestricted)
+        } else if lower.contains("available in your country")
+            || lower.contains("geo restriction")
+            || lower.contains("geo-restricted")
+        {
+            Some(DownloadError::GeoBlocked)
+        } else if lower.contains("no space left on device") || lower.contains("errno 28") {
+            Some(DownloadError::DiskFull)
+        } else if lower.contains("ffmpeg not found")
+            || lower.contains("ffprobe and ffmpeg not found")
+            || lower.contains("ffmpeg/avconv not found")
+        {
+            Some(DownloadError::FfmpegMissing)
+        } else if lower.contains("http error 429") || lower.contains("too many requests") {
+            Some(DownloadError::Throttled(429))
+        } else if lower.contains("http error 403") {
+            Some(DownloadError::Throttled(403))
+        } else if lower.contains("<urlopen error")
+            || lower.contains("timed out")
+            || lower.contains("connection reset")
+            || lower.contains("connection refused")
+            || lower.contains("connection aborted")
+            || lower.contains("remote end closed connection")
+            || lower.contains("temporary failure in name resolution")
+            || lower.contains("name or service not known")
+            || lower.contains("nodename nor servname")
+            || lower.contains("network is unreachable")
+        {
+            Some(DownloadError::Network)
+        } else if lower.contains("unable to extract")
+            || lower.contains("nsig extraction failed")
+            || lower.contains("confirm you are on the latest version")
+        {
+            Some(DownloadError::ExtractorOutdated)
+        } else if lower.contains("requested format is not available")
+            || lower.contains("only images are available")
+        {
+            Some(DownloadError::FormatUnavailable)
+        } else if lower.contains("video unavailable")
+            || lower.contains("this video is unavailable")
+            || lower.contains("this video has been removed")
+        {
+            Some(DownloadError::VideoUnavailable)
+        } else {
+            None
+        }
+    }
+    
+    pub fn message(&self) -> String {
+        match self {
+            DownloadError::VideoUnavailable => "This video is unavailable or has been removed.".to_string(),
+            DownloadError::Private => "This video is private.".to_string(),
+            DownloadError::GeoBlocked => "This video is not available in your country.".to_string(),
+            DownloadError::AgeRestricted => "This video is age-restricted and needs a signed-in account.".to_string(),
+            DownloadError::Throttled(429) => "YouTube is rate limiting downloads (HTTP 429).".to_string(),
+            DownloadError::Throttled(status) => format!("YouTube refused the download (HTTP {}).", status),
+            DownloadError::Network => "Couldn't reach YouTube. Check your internet connection.".to_string(),
+            DownloadError::FfmpegMissing => "ffmpeg is missing, so the audio couldn't be converted to MP3.".to_string(),
+            DownloadError::DiskFull => "The download directory is out of disk space.".to_string(),
+            DownloadError::ExtractorOutdated => "yt-dlp couldn't read the YouTube page and is probably out of date.".to_string(),
+            DownloadError::FormatUnavailable => "No downloadable audio format was offered for this video.".to_string(),
+            DownloadError::Other(message) => message.clone(),
+        }
+    }
+    
+    // Whether the next attempt might get past this failure: throttling and network errors
+    // can clear after a wait, an outdated extractor only with another player client, and a
+    // missing format with another client or format selection
+    pub fn is_retryable(&self, client_changes: bool, format_changes: bool) -> bool {
+        match self {
+            DownloadError::Throttled(_) | DownloadError::Network => true,
+            DownloadError::ExtractorOutdated => client_changes,
+            DownloadError::FormatUnavailable => client_changes || format_changes,
+            _ => false,
+        }
+    }
+    
+    pub fn suggested_action(&self) -> Option<SuggestedAction> {
+        match self {
+            DownloadError::AgeRestricted => Some(SuggestedAction::EnableCookies),
+            DownloadError::Throttled(_) | DownloadError::Network => Some(SuggestedAction::RetryLater),
+            DownloadError::FfmpegMissing => Some(SuggestedAction::InstallFfmpeg),
+            DownloadError::ExtractorOutdated => Some(SuggestedAction::UpdateYtDlp),
+            DownloadError::FormatUnavailable => Some(SuggestedAction::UpdateYtDlp),
+            DownloadError::Other(_) => Some(SuggestedAction::RetryLater),
+            DownloadError::VideoUnavailable
+            | DownloadError::Private
+            | DownloadError::GeoBlocked
+            | DownloadError::DiskFull => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn classify(stderr: &str) -> DownloadError {
+        DownloadError::classify(stderr.lines(), Some(1))
+    }
+    
+    #[test]
+    fn recognises_error_lines() {
+        assert_eq!(
+            classify("ERROR: [youtube] dQw4w9WgXcQ: Private video. Sign in if you've been granted access to this video"),
+            DownloadError::Private
+        );
+        assert_eq!(
+            classify("ERROR: [youtube] dQw4w9WgXcQ: Sign in to confirm your age. This video may be inappropriate for some users. Use --cookies-from-browser or --cookies for the authentication."),
+            DownloadError::AgeRestricted
+        );
+        assert_eq!(
+            classify("ERROR: [youtube] dQw4w9WgXcQ: The uploader has not made this video available in your country"),
+            DownloadError::GeoBlocked
+        );
+        assert_eq!(
+            classify("ERROR: [youtube] dQw4w9WgXcQ: Video unavailable. This video has been removed by the uploader"),
+            DownloadError::VideoUnavailable
+        );
+        assert_eq!(
+            classify("ERROR: unable to download video data: HTTP Error 403: Forbidden"),
+            DownloadError::Throttled(403)
+        );
+        assert_eq!(
+            classify("ERROR: [youtube] dQw4w9WgXcQ: Unable to download API page: HTTP Error 429: Too Many Requests (caused by <HTTPError 429: Too Many Requests>)"),
+            DownloadError::Throttled(429)
+        );
+        assert_eq!(
+            classify("ERROR: Postprocessing: ffprobe and ffmpeg not found. Please install or provide the path using --ffmpeg-location"),
+            DownloadError::FfmpegMissing
+        );
+        assert_eq!(
+            classify("ERROR: unable to write data: [Errno 28] No space left on device"),
+            DownloadError::DiskFull
+        );
+        assert_eq!(
+            classify("ERROR: [youtube] dQw4w9WgXcQ: Unable to download webpage: <urlopen error [Errno -3] Temporary failure in name resolution> (caused by URLError(gaierror(-3, 'Temporary failure in name resolution')))"),
+            DownloadError::Network
+        );
+    }
+    
+    #[test]
+    fn retries_only_transient_failures() {
+        assert!(DownloadError::Throttled(429).is_retryable(false, false));
+        assert!(DownloadError::Network.is_retryable(false, false));
+        assert!(DownloadError::ExtractorOutdated.is_retryable(true, false));
+        assert!(!DownloadError::ExtractorOutdated.is_retryable(false, true));
+        assert!(DownloadError::FormatUnavailable.is_retryable(false, true));
+        assert!(!DownloadError::Private.is_retryable(true, true));
+        assert!(!DownloadError::Other("Failed to run yt-dlp (is it installed?): No such file or directory".to_string())
+            .is_retryable(true, true));
+    }
+    
+    #[test]
+    fn error_lines_beat_warnings() {
+        let stderr = "\
+WARNING: [youtube] dQw4w9WgXcQ: nsig extraction failed: Some formats may be missing
+         n = 6Nn8ANFe9nuAj ; player = https://www.youtube.com/s/player/b7910ca8/player_ias.vflset/en_US/base.js
+ERROR: [youtube] dQw4w9WgXcQ: Requested format is not available. Use --list-formats for a list of available formats";
+        assert_eq!(classify(stderr), DownloadError::FormatUnavailable);
+    }
+    
+    #[test]
+    fn falls_back_to_warnings() {
+        let stderr = "\
+WARNING: [youtube] Unable to extract yt initial data; please report this issue on  https://github.com/yt-dlp/yt-dlp/issues?q= , filling out the appropriate issue template. Confirm you are on the latest version using  yt-dlp -U
+ERROR: [youtube] dQw4w9WgXcQ: Failed to parse JSON (caused by JSONDecodeError)";
+        assert_eq!(classify(stderr), DownloadError::ExtractorOutdated);
+    }
+    
+    #[test]
+    fn ignores_debug_and_progress_lines() {
+        let stderr = "\
+[debug] Command-line config: ['-x', '--audio-format', 'mp3', '--verbose', '-o', 'Private video reaction.%(ext)s', 'https://www.youtube.com/watch?v=dQw4w9WgXcQ']
+[debug] Encodings: locale UTF-8, fs utf-8, pref UTF-8, out utf-8, error utf-8, screen utf-8
+[youtube] dQw4w9WgXcQ: Downloading webpage
+[download] Destination: Private video reaction.webm
+ERROR: unable to download video data: HTTP Error 403: Forbidden";
+        assert_eq!(classify(stderr), DownloadError::Throttled(403));
+        
+        let stderr = "\
+[debug] Command-line config: ['-o', 'No space left on device.%(ext)s', 'https://www.youtube.com/watch?v=dQw4w9WgXcQ']
+ERROR: [generic] Unsupported URL: https://example.com/";
+        assert_eq!(
+            classify(stderr),
+            DownloadError::Other("[generic] Unsupported URL: https://example.com/".to_string())
+        );
+    }
+    
+    #[test]
+    fn reads_wrapped_search_errors() {
+        let error = "Failed to get video info: ERROR: [youtube] dQw4w9WgXcQ: Sign in to confirm your age. This video may be inappropriate for some users.";
+        assert_eq!(DownloadError::classify(error.lines(), None), DownloadError::AgeRestricted);
+    }
+    
+    #[test]
+    fn reports_the_exit_code_without_an_error_line() {
+        assert_eq!(
+            classify("[debug] Command-line config: []"),
+            DownloadError::Other("yt-dlp failed with exit code: Some(1). Check logs for details.".to_string())
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use crate::ytdlp_update::{expected_sha256, sha256_hex};
+use std::path::{Path, PathBuf};
+
+// A downloadable archive and where its SHA-256 sum is published
+struct Archive {
+    url: &'static str,
+    checksum_url: &'static str,
+    file_name: &'static str, // as named in the checksum file
+}
+
+// Static ffmpeg builds for the current platform, from sources that publish SHA-256 sums
+fn static_build_archives() -> Result<Vec<Archive>, String> {
+    if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        // Martin Riedl's builds ship ffmpeg and ffprobe as separate zips
+        Ok(vec![
+            Archive {
+                url: "https://ffmpeg.martin-riedl.de/redirect/latest/macos/arm64/release/ffmpeg.zip",
+                checksum_url: "https://ffmpeg.martin-riedl.de/redirect/latest/macos/arm64/release/ffmpeg.zip.sha256",
+                file_name: "ffmpeg.zip",
+            },
+            Archive {
+                url: "https://ffmpeg.martin-riedl.de/redirect/latest/macos/arm64/release/ffprobe.zip",
+                checksum_url: "https://ffmpeg.martin-riedl.de/redirect/latest/macos/arm64/release/ffprobe.zip.sha256",
+                file_name: "ffprobe.zip",
+            },
+        ])
+    } else if cfg!(target_os = "macos") {
+        Ok(vec![
+            Archive {
+                url: "https://ffmpeg.martin-riedl.de/redirect/latest/macos/amd64/release/ffmpeg.zip",
+                checksum_url: "https://ffmpeg.martin-riedl.de/redirect/latest/macos/amd64/release/ffmpeg.zip.sha256",
+                file_name: "ffmpeg.zip",
+            },
+            Archive {
+                url: "https://ffmpeg.martin-riedl.de/redirect/latest/macos/amd64/release/ffprobe.zip",
+                checksum_url: "https://ffmpeg.martin-riedl.de/redirect/latest/macos/amd64/release/ffprobe.zip.sha256",
+                file_name: "ffprobe.zip",
+            },
+        ])
+    } else if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+        Ok(vec![Archive {
+            url: "https://github.com/yt-dlp/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-linux64-gpl.tar.xz",
+            checksum_url: "https://github.com/yt-dlp/FFmpeg-Builds/releases/download/latest/checksums.sha256",
+            file_name: "ffmpeg-master-latest-linux64-gpl.tar.xz",
+        }])
+    } else if cfg!(all(target_os = "linux", target_arch = "aarch64")) {
+        Ok(vec![Archive {
+            url: "https://github.com/yt-dlp/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-linuxarm64-gpl.tar.xz",
+            checksum_url: "https://github.com/yt-dlp/FFmpeg-Builds/releases/download/latest/checksums.sha256",
+            file_name: "ffmpeg-master-latest-linuxarm64-gpl.tar.xz",
+        }])
+    } else {
+        Err("No static ffmpeg build available for this platform".to_string())
+    }
+}
+
+// Resolve the ffmpeg and ffprobe binaries from an --ffmpeg-location value,
+// which may be either a directory or the ffmpeg binary itself
+pub fn binaries(location: &Path) -> (PathBuf, PathBuf) {
+    if location.is_dir() {
+        (location.join("ffmpeg"), location.join("ffprobe"))
+    } else {
+        (location.to_path_buf(), location.with_file_name("ffprobe"))
+    }
+}
+
+// Ask ffmpeg or ffprobe for its version, e.g. "6.1.1" from "ffmpeg version 6.1.1 Copyright ..."
+pub async fn tool_version(binary: PathBuf) -> Option<String> {
+    let output = tokio::process::Command::new(binary)
+        .arg("-version")
+        .output()
+        .await
+        .ok()?;
+    
+    if !output.status.success() {
+        return None;
+    }
+    
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(2))
+        .map(|version| version.to_string())
+}
+
+// Download a static ffmpeg build and install ffmpeg and ffprobe into install_dir
+pub async fn install_static_build(install_dir: PathBuf) -> Result<(), String> {
+    std::fs::create_dir_all(&install_dir)
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    
+    let work_dir = std::env::temp_dir().join("nightingale-ffmpeg");
+    let _ = std::fs::remove_dir_all(&work_dir);
+    std::fs::create_dir_all(&work_dir)
+        .map_err(|e| format!("Failed to create temporary directory: {}", e))?;
+    
+    let result = async {
+        let client = reqwest::Client::new();
+        
+        for archive in static_build_archives()? {
+            let checksums = client
+                .get(archive.checksum_url)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to download checksums: {}", e))?
+                .error_for_status()
+                .map_err(|e| format!("Failed to download checksums: {}", e))?
+                .text()
+                .await
+                .map_err(|e| format!("Failed to read checksums: {}", e))?;
+            let expected = expected_sha256(&checksums, archive.file_name)
+                .ok_or_else(|| format!("No checksum published for {}", archive.file_name))?;
+            
+            let bytes = client
+                .get(archive.url)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to download ffmpeg: {}", e))?
+                .error_for_status()
+                .map_err(|e| format!("Failed to download ffmpeg: {}", e))?
+                .bytes()
+                .await
+                .map_err(|e| format!("Failed to read download: {}", e))?;
+            
+            let actual = sha256_hex(&bytes);
+            
+            if actual != expected {
+                return Err(format!(
+                    "Checksum mismatch for {} (expected {}, got {})",
+                    archive.file_name, expected, actual
+                ));
+            }
+            
+            let archive_path = work_dir.join(archive.file_name);
+            std::fs::write(&archive_path, &bytes)
+                .map_err(|e| format!("Failed to write archive: {}", e))?;
+            
+            extract_archive(&archive_path, &work_dir).await?;
+        }
+        
+        for name in ["ffmpeg", "ffprobe"] {
+            let binary = find_file(&work_dir, name)
+                .ok_or_else(|| format!("{} not found in the downloaded archive", name))?;
+            let target = install_dir.join(name);
+            
+            std::fs::copy(&binary, &target)
+                .map_err(|e| format!("Failed to install {}: {}", name, e))?;
+            
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o755))
+                    .map_err(|e| format!("Failed to set executable permissions: {}", e))?;
+            }
+        }
+        
+        Ok(())
+    }.await;
+    
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result
+}
+
+// Unpack a .zip or .tar.xz archive with the system tools
+async fn extract_archive(archive: &Path, dest: &Path) -> Result<(), String> {
+    let is_zip = archive.extension().is_some_and(|ext| ext == "zip");
+    
+    let mut cmd = if is_zip {
+        let mut cmd = tokio::process::Command::new("unzip");
+        cmd.arg("-o").arg(archive).arg("-d").arg(dest);
+        cmd
+    } else {
+        let mut cmd = tokio::process::Command::new("tar");
+        cmd.arg("-xJf").arg(archive).arg("-C").arg(dest);
+        cmd
+    };
+    
+    let output = cmd.output()
+        .await
+        .map_err(|e| format!("Failed to extract {}: {}", archive.display(), e))?;
+    
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to extract {}: {}",
+            archive.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    
+    Ok(())
+}
+
+// Find a file by name anywhere below dir
+fn find_file(dir: &Path, name: &str) -> Option<PathBuf> {
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if let Some(found) = find_file(&path, name) {
+                return Some(found);
+            }
+        } else if path.file_name().is_some_and(|n| n == name) {
+            return Some(path);
+        }
+    }
+    None
+}
@@ -0,0 +1,217 @@
+use crate::loudness::Loudness;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Unpinned searches kept before the oldest are dropped
+const MAX_HISTORY_ENTRIES: usize = 100;
+
+// Suggestions shown under the search box at once
+const MAX_SUGGESTIONS: usize = 8;
+
+// The result that was downloaded from a search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DownloadedResult {
+    pub video_id: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub query: String,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub downloaded: Option<DownloadedResult>,
+}
+
+// Past searches, most recent first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchHistory {
+    entries: Vec<HistoryEntry>,
+}
+
+fn history_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("nightingale").join("search_history.json"))
+}
+
+// Searches differing only in case or surrounding whitespace are the same search
+fn same_query(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+impl SearchHistory {
+    pub fn load() -> Self {
+        history_file()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+    
+    pub fn save(&self) -> Result<(), String> {
+        let path = history_file().ok_or_else(|| "Could not find config directory".to_string())?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize search history: {}", e))?;
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to write search history: {}", e))
+    }
+    
+    pub fn get(&self, query: &str) -> Option<&HistoryEntry> {
+        self.entries.iter().find(|entry| same_query(&entry.query, query))
+    }
+    
+    // Move a search to the top, keeping its pin and downloaded result
+    pub fn record(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+        
+        let entry = match self.entries.iter().position(|entry| same_query(&entry.query, query)) {
+            Some(index) => {
+                let mut entry = self.entries.remove(index);
+                entry.query = query.to_string();
+                entry
+            }
+            None => HistoryEntry {
+                query: query.to_string(),
+                pinned: false,
+                downloaded: None,
+            },
+        };
+        self.entries.insert(0, entry);
+        
+        // Pinned searches are never dropped
+        let mut unpinned = 0;
+        self.entries.retain(|entry| {
+            if entry.pinned {
+                return true;
+            }
+            unpinned += 1;
+            unpinned <= MAX_HISTORY_ENTRIES
+        });
+    }
+    
+    pub fn record_download(&mut self, query: &str, video_id: &str, title: &str) {
+        if self.get(query).is_none() {
+            self.record(query);
+        }
+        
+        if let Some(entry) = self.entries.iter_mut().find(|entry| same_query(&entry.query, query)) {
+            entry.downloaded = Some(DownloadedResult {
+                video_id: video_id.to_string(),
+                title: title.to_string(),
+            });
+        }
+    }
+    
+    pub fn toggle_pin(&mut self, query: &str) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| same_query(&entry.query, query)) {
+            entry.pinned = !entry.pinned;
+        }
+    }
+    
+    pub fn remove(&mut self, query: &str) {
+        self.entries.retain(|entry| !same_query(&entry.query, query));
+    }
+    
+    pub fn pinned(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter().filter(|entry| entry.pinned)
+    }
+    
+    // Searches matching what's been typed so far, pinned ones first
+    pub fn suggestions(&self, input: &str) -> Vec<&HistoryEntry> {
+        let input = input.trim().to_lowercase();
+        let matches = |entry: &&HistoryEntry| input.is_empty() || entry.query.to_lowercase().contains(&input);
+        
+        self.entries.iter()
+            .filter(|entry| entry.pinned)
+            .filter(matches)
+            .chain(self.entries.iter().filter(|entry| !entry.pinned).filter(matches))
+            .take(MAX_SUGGESTIONS)
+            .collect()
+    }
+}
+
+// Completed downloads kept before the oldest are dropped
+const MAX_DOWNLOAD_RECORDS: usize = 1000;
+
+// A finished download, with what post-processing found
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadRecord {
+    pub video_id: String,
+    pub title: String,
+    pub file_path: PathBuf,
+    pub downloaded_at: u64, // seconds since the Unix epoch
+    #[serde(default)]
+    pub loudness: Option<Loudness>,
+}
+
+// Every completed download, most recent first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloadHistory {
+    records: Vec<DownloadRecord>,
+}
+
+fn download_history_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("nightingale").join("download_history.json"))
+}
+
+impl DownloadHistory {
+    pub fn load() -> Self {
+        download_history_file()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+    
+    pub fn save(&self) -> Result<(), String> {
+        let path = download_history_file().ok_or_else(|| "Could not find config directory".to_string())?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize download history: {}", e))?;
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to write download history: {}", e))
+    }
+    
+    // A download of the same video again replaces its earlier record
+    pub fn record(&mut self, video_id: &str, title: &str, file_path: &Path) {
+        self.records.retain(|record| record.video_id != video_id);
+        self.records.insert(0, DownloadRecord {
+            video_id: video_id.to_string(),
+            title: title.to_string(),
+            file_path: file_path.to_path_buf(),
+            downloaded_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or(0),
+            loudness: None,
+        });
+        self.records.truncate(MAX_DOWNLOAD_RECORDS);
+    }
+    
+    pub fn set_loudness(&mut self, video_id: &str, loudness: Loudness) {
+        if let Some(record) = self.records.iter_mut().find(|record| record.video_id == video_id) {
+            record.loudness = Some(loudness);
+        }
+    }
+    
+    pub fn records(&self) -> &[DownloadRecord] {
+        &self.records
+    }
+    
+    pub fn contains(&self, video_id: &str) -> bool {
+        self.records.iter().any(|record| record.video_id == video_id)
+    }
+}
@@ -0,0 +1,261 @@
+use crate::audio_format::AudioFormat;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// ReplayGain 2.0 reference level
+const REPLAYGAIN_REFERENCE_LUFS: f64 = -18.0;
+
+// Ceiling for normalized tracks, leaving headroom for the MP3 encoder
+const TRUE_PEAK_LIMIT_DBTP: f64 = -1.5;
+
+// What to do with a track once its loudness is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoudnessMode {
+    #[default]
+    Off,
+    Normalize, // re-encode to the target loudness
+    ReplayGain, // leave the audio alone and tag the gain for players to apply
+}
+
+impl LoudnessMode {
+    pub const ALL: [LoudnessMode; 3] = [
+        LoudnessMode::Off,
+        LoudnessMode::Normalize,
+        LoudnessMode::ReplayGain,
+    ];
+}
+
+impl std::fmt::Display for LoudnessMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            LoudnessMode::Off => "Off",
+            LoudnessMode::Normalize => "Normalize volume",
+            LoudnessMode::ReplayGain => "Write ReplayGain tags",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Targets loudnorm accepts, in LUFS
+pub const MIN_TARGET_LUFS: f64 = -70.0;
+pub const MAX_TARGET_LUFS: f64 = -5.0;
+
+// A target typed as "14", "-14" or "14.5"; the settings show the minus sign outside the
+// field, so both spellings mean the same negative loudness
+pub fn parse_target(input: &str) -> Option<f64> {
+    let input = input.trim();
+    let magnitude = input.strip_prefix('-').unwrap_or(input).trim();
+    if magnitude.starts_with(['-', '+']) {
+        return None;
+    }
+    
+    let target = -magnitude.parse::<f64>().ok()?;
+    (MIN_TARGET_LUFS..=MAX_TARGET_LUFS).contains(&target).then_some(target)
+}
+
+// EBU R128 measurement of a track and the gain applied or tagged because of it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Loudness {
+    pub integrated_lufs: f64,
+    pub true_peak_dbtp: f64,
+    pub loudness_range_lu: f64,
+    pub gain_db: f64,
+    pub mode: LoudnessMode,
+}
+
+impl Loudness {
+    pub fn describe(&self) -> String {
+        match self.mode {
+            LoudnessMode::Normalize => format!(
+                "Normalized from {:.1} LUFS ({:+.1} dB)",
+                self.integrated_lufs, self.gain_db
+            ),
+            LoudnessMode::ReplayGain => format!(
+                "Measured {:.1} LUFS, ReplayGain {:+.2} dB",
+                self.integrated_lufs, self.gain_db
+            ),
+            LoudnessMode::Off => format!("Measured {:.1} LUFS", self.integrated_lufs),
+        }
+    }
+}
+
+// The parts of ffmpeg's loudnorm report needed for the second pass
+struct Analysis {
+    input_i: f64,
+    input_tp: f64,
+    input_lra: f64,
+    input_thresh: f64,
+    target_offset: f64,
+    sample_rate: Option<u32>,
+}
+
+// Measure the file, then normalize it or tag it according to the mode
+pub async fn process(ffmpeg: PathBuf, file: PathBuf, mode: LoudnessMode, target_lufs: f64) -> Result<Loudness, String> {
+    let analysis = analyze(&ffmpeg, &file, target_lufs).await?;
+    
+    let gain_db = match mode {
+        LoudnessMode::ReplayGain => {
+            let gain_db = REPLAYGAIN_REFERENCE_LUFS - analysis.input_i;
+            write_replaygain(&ffmpeg, &file, gain_db, analysis.input_tp).await?;
+            gain_db
+        }
+        LoudnessMode::Normalize => {
+            normalize(&ffmpeg, &file, target_lufs, &analysis).await?;
+            target_lufs - analysis.input_i
+        }
+        LoudnessMode::Off => 0.0,
+    };
+    
+    Ok(Loudness {
+        integrated_lufs: analysis.input_i,
+        true_peak_dbtp: analysis.input_tp,
+        loudness_range_lu: analysis.input_lra,
+        gain_db,
+        mode,
+    })
+}
+
+fn loudnorm_filter(target_lufs: f64) -> String {
+    format!("loudnorm=I={:.1}:TP={:.1}:LRA=11", target_lufs, TRUE_PEAK_LIMIT_DBTP)
+}
+
+// First loudnorm pass: measures without writing anything
+async fn analyze(ffmpeg: &Path, file: &Path, target_lufs: f64) -> Result<Analysis, String> {
+    let output = tokio::process::Command::new(ffmpeg)
+        .arg("-hide_banner")
+        .arg("-nostats")
+        .arg("-i").arg(file)
+        .arg("-af").arg(format!("{}:print_format=json", loudnorm_filter(target_lufs)))
+        .arg("-f").arg("null")
+        .arg("-")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(format!("Loudness analysis failed: {}", stderr.trim()));
+    }
+    
+    // The report is the last JSON object ffmpeg prints
+    let report = stderr.rfind('{')
+        .and_then(|start| stderr[start..].find('}').map(|end| &stderr[start..=start + end]))
+        .ok_or_else(|| "ffmpeg did not report the loudness".to_string())?;
+    let report: serde_json::Value = serde_json::from_str(report)
+        .map_err(|e| format!("Failed to parse loudness report: {}", e))?;
+    
+    // loudnorm reports its numbers as strings
+    let field = |name: &str| -> Result<f64, String> {
+        report[name].as_str()
+            .and_then(|value| value.trim().parse().ok())
+            .filter(|value: &f64| value.is_finite())
+            .ok_or_else(|| format!("Loudness report has no usable {} (is the track silent?)", name))
+    };
+    
+    // e.g. "Stream #0:0: Audio: mp3, 48000 Hz, stereo, fltp, 160 kb/s"
+    let sample_rate = stderr.lines()
+        .find(|line| line.contains("Audio:"))
+        .and_then(|line| line.split(',').find_map(|part| part.trim().strip_suffix(" Hz")?.parse().ok()));
+    
+    Ok(Analysis {
+        input_i: field("input_i")?,
+        input_tp: field("input_tp")?,
+        input_lra: field("input_lra")?,
+        input_thresh: field("input_thresh")?,
+        target_offset: field("target_offset")?,
+        sample_rate,
+    })
+}
+
+// Second loudnorm pass with the measured values, re-encoding in place
+async fn normalize(ffmpeg: &Path, file: &Path, target_lufs: f64, analysis: &Analysis) -> Result<(), String> {
+    let filter = format!(
+        "{}:measured_I={:.2}:measured_TP={:.2}:measured_LRA={:.2}:measured_thresh={:.2}:offset={:.2}:linear=true",
+        loudnorm_filter(target_lufs),
+        analysis.input_i,
+        analysis.input_tp,
+        analysis.input_lra,
+        analysis.input_thresh,
+        analysis.target_offset,
+    );
+    
+    let format = AudioFormat::from_path(file);
+    let mut cmd = tokio::process::Command::new(ffmpeg);
+    cmd.arg("-y")
+        .arg("-v").arg("error")
+        .arg("-i").arg(file)
+        .arg("-map").arg("0")
+        .arg("-map_metadata").arg("0")
+        .arg("-c:v").arg("copy")
+        .arg("-af").arg(filter)
+        .args(format.encoder_args());
+    
+    // loudnorm resamples to 192 kHz internally; go back to the original rate
+    if let Some(sample_rate) = analysis.sample_rate {
+        cmd.arg("-ar").arg(sample_rate.to_string());
+    }
+    
+    cmd.args(format.tag_args());
+    rewrite(cmd, file).await
+}
+
+// Tag the track gain and peak without touching the audio
+async fn write_replaygain(ffmpeg: &Path, file: &Path, gain_db: f64, true_peak_dbtp: f64) -> Result<(), String> {
+    let peak = 10f64.powf(true_peak_dbtp / 20.0);
+    
+    let mut cmd = tokio::process::Command::new(ffmpeg);
+    cmd.arg("-y")
+        .arg("-v").arg("error")
+        .arg("-i").arg(file)
+        .arg("-map").arg("0")
+        .arg("-map_metadata").arg("0")
+        .arg("-c").arg("copy")
+        .arg("-metadata").arg(format!("REPLAYGAIN_TRACK_GAIN={:+.2} dB", gain_db))
+        .arg("-metadata").arg(format!("REPLAYGAIN_TRACK_PEAK={:.6}", peak))
+        .args(AudioFormat::from_path(file).tag_args());
+    rewrite(cmd, file).await
+}
+
+// Run an ffmpeg command writing to a staged copy, then replace the original with it
+async fn rewrite(mut cmd: tokio::process::Command, file: &Path) -> Result<(), String> {
+    let staged = file.with_extension(format!("loudness.{}", AudioFormat::from_path(file).extension()));
+    
+    let output = cmd.arg(&staged)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&staged);
+        return Err(format!("ffmpeg failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    
+    std::fs::rename(&staged, file)
+        .map_err(|e| format!("Failed to replace {}: {}", file.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parses_targets_with_or_without_the_sign() {
+        assert_eq!(parse_target("14"), Some(-14.0));
+        assert_eq!(parse_target(" -23 "), Some(-23.0));
+        assert_eq!(parse_target("14.5"), Some(-14.5));
+        assert_eq!(parse_target("14."), Some(-14.0));
+    }
+    
+    #[test]
+    fn rejects_invalid_targets() {
+        assert_eq!(parse_target(""), None);
+        assert_eq!(parse_target("-"), None);
+        assert_eq!(parse_target("--14"), None);
+        assert_eq!(parse_target("+14"), None);
+        assert_eq!(parse_target("1-4"), None);
+        assert_eq!(parse_target("4"), None);
+        assert_eq!(parse_target("80"), None);
+        assert_eq!(parse_target("NaN"), None);
+    }
+}
@@ -0,0 +1,265 @@
+use crate::audio_format::AudioFormat;
+use std::path::{Path, PathBuf};
+
+// Subtitle languages to ask for: every uploaded track, but only the automatic captions in
+// the video's own language; the other automatic ones are machine translations
+const MANUAL_LANGUAGES: &str = "all,-live_chat";
+const AUTOMATIC_LANGUAGES: &str = ".*-orig";
+
+// What to do with the lyrics of one download
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LyricsOptions {
+    pub save_lrc: bool, // .lrc file next to the audio
+    pub embed: bool, // unsynchronized lyrics in the ID3 USLT frame
+}
+
+impl LyricsOptions {
+    pub fn is_enabled(&self) -> bool {
+        self.save_lrc || self.embed
+    }
+}
+
+// One timed line of an LRC file
+struct LyricLine {
+    timestamp: String, // "[mm:ss.xx]"
+    text: String,
+}
+
+// Fetch the video's subtitles or automatic captions as LRC with yt-dlp, then save and/or
+// embed them for the downloaded file; returns a summary for the download status
+pub async fn fetch(
+    video_id: String,
+    audio: PathBuf,
+    options: LyricsOptions,
+    cookie_args: Vec<String>,
+    ffmpeg_location: Option<String>,
+) -> Result<String, String> {
+    let work_dir = std::env::temp_dir().join(format!("nightingale-lyrics-{}", video_id));
+    let _ = std::fs::remove_dir_all(&work_dir);
+    std::fs::create_dir_all(&work_dir)
+        .map_err(|e| format!("Failed to create {}: {}", work_dir.display(), e))?;
+    
+    let result = fetch_into(&video_id, &audio, options, &cookie_args, ffmpeg_location.as_deref(), &work_dir).await;
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result
+}
+
+async fn fetch_into(
+    video_id: &str,
+    audio: &Path,
+    options: LyricsOptions,
+    cookie_args: &[String],
+    ffmpeg_location: Option<&str>,
+    work_dir: &Path,
+) -> Result<String, String> {
+    // Subtitles the uploader wrote beat automatic captions, which are only used untranslated
+    let mut files = download_captions(video_id, false, cookie_args, ffmpeg_location, work_dir).await?;
+    if files.is_empty() {
+        files = download_captions(video_id, true, cookie_args, ffmpeg_location, work_dir).await?;
+    }
+    
+    let lrc = preferred_track(&files)
+        .ok_or_else(|| "This video has no subtitles or captions".to_string())?;
+    let contents = std::fs::read_to_string(lrc)
+        .map_err(|e| format!("Failed to read {}: {}", lrc.display(), e))?;
+    
+    let lines = clean_lines(&contents);
+    if lines.is_empty() {
+        return Err("The captions contain no lyrics".to_string());
+    }
+    
+    let mut done = Vec::new();
+    
+    if options.save_lrc {
+        let lrc_path = audio.with_extension("lrc");
+        let text: String = lines.iter().map(|line| format!("{}{}\n", line.timestamp, line.text)).collect();
+        std::fs::write(&lrc_path, text)
+            .map_err(|e| format!("Failed to write {}: {}", lrc_path.display(), e))?;
+        done.push("saved .lrc");
+    }
+    
+    if options.embed {
+        if AudioFormat::from_path(audio) == AudioFormat::Mp3 {
+            let text = lines.iter().map(|line| line.text.as_str()).collect::<Vec<_>>().join("\n");
+            embed(audio, text)?;
+            done.push("embedded");
+        } else {
+            done.push("not embedded (only MP3 tags are supported)");
+        }
+    }
+    
+    Ok(format!("Lyrics {} ({} lines)", done.join(" and "), lines.len()))
+}
+
+// Download uploaded subtitles or automatic captions as lyrics.<lang>.lrc files
+async fn download_captions(
+    video_id: &str,
+    automatic: bool,
+    cookie_args: &[String],
+    ffmpeg_location: Option<&str>,
+    work_dir: &Path,
+) -> Result<Vec<PathBuf>, String> {
+    let mut cmd = tokio::process::Command::new(crate::find_ytdlp());
+    cmd.arg("--skip-download")
+        .arg("--no-playlist");
+    
+    if automatic {
+        cmd.arg("--write-auto-subs").arg("--sub-langs").arg(AUTOMATIC_LANGUAGES);
+    } else {
+        cmd.arg("--write-subs").arg("--sub-langs").arg(MANUAL_LANGUAGES);
+    }
+    
+    cmd.arg("--convert-subs").arg("lrc")
+        .args(cookie_args);
+    
+    if let Some(ffmpeg_location) = ffmpeg_location {
+        cmd.arg("--ffmpeg-location").arg(ffmpeg_location);
+    }
+    
+    let output = cmd
+        .arg("-o").arg(work_dir.join("lyrics.%(ext)s"))
+        .arg(format!("https://www.youtube.com/watch?v={}", video_id))
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run yt-dlp: {}", e))?;
+    
+    if !output.status.success() {
+        return Err(format!("Failed to get captions: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    
+    Ok(std::fs::read_dir(work_dir)
+        .map_err(|e| format!("Failed to read {}: {}", work_dir.display(), e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "lrc"))
+        .collect())
+}
+
+// English if there is an English track, otherwise the first language by name
+fn preferred_track(files: &[PathBuf]) -> Option<&PathBuf> {
+    files.iter().min_by_key(|path| {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        // lyrics.<lang>.lrc, where lang is "en", "en-US" or "en-orig"
+        let lang = name.strip_prefix("lyrics.")
+            .and_then(|rest| rest.strip_suffix(".lrc"))
+            .unwrap_or_default()
+            .to_string();
+        let english = lang == "en" || lang.starts_with("en-");
+        (!english, lang)
+    })
+}
+
+// Keep the timed lines with words in them; automatic captions repeat each line as it
+// scrolls and mark instrumental parts with [Music] or ♪
+fn clean_lines(contents: &str) -> Vec<LyricLine> {
+    let mut lines: Vec<LyricLine> = Vec::new();
+    
+    for line in contents.lines() {
+        let Some(rest) = line.strip_prefix('[') else {
+            continue;
+        };
+        let Some((time, text)) = rest.split_once(']') else {
+            continue;
+        };
+        // Skip header tags like [ar:...]; timestamps start with a digit
+        if !time.starts_with(|c: char| c.is_ascii_digit()) {
+            continue;
+        }
+        
+        let text = strip_markup(text);
+        let words = text.replace(['♪', '♫'], "");
+        let words = words.trim();
+        if words.is_empty() || words.eq_ignore_ascii_case("[music]") || words.eq_ignore_ascii_case("[applause]") {
+            continue;
+        }
+        if lines.last().is_some_and(|last| last.text == words) {
+            continue;
+        }
+        
+        lines.push(LyricLine {
+            timestamp: format!("[{}]", time),
+            text: words.to_string(),
+        });
+    }
+    
+    lines
+}
+
+// Drop inline <c> and <00:00:01.000> tags left over from the caption format
+fn strip_markup(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => result.push(c),
+            _ => {}
+        }
+    }
+    result
+}
+
+fn embed(audio: &Path, text: String) -> Result<(), String> {
+    use id3::TagLike;
+    
+    let mut tag = match id3::Tag::read_from_path(audio) {
+        Ok(tag) => tag,
+        Err(id3::Error { kind: id3::ErrorKind::NoTag, .. }) => id3::Tag::new(),
+        Err(e) => return Err(format!("Failed to read tags from {}: {}", audio.display(), e)),
+    };
+    
+    tag.remove_all_lyrics();
+    tag.add_frame(id3::frame::Lyrics {
+        lang: "und".to_string(),
+        description: String::new(),
+        text,
+    });
+    tag.write_to_path(audio, id3::Version::Id3v23)
+        .map_err(|e| format!("Failed to write lyrics to {}: {}", audio.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn prefers_english_tracks() {
+        let files = [PathBuf::from("/tmp/lyrics.de.lrc"), PathBuf::from("/tmp/lyrics.en-GB.lrc")];
+        assert_eq!(preferred_track(&files), Some(&files[1]));
+        
+        let files = [PathBuf::from("/tmp/lyrics.ja.lrc"), PathBuf::from("/tmp/lyrics.es.lrc")];
+        assert_eq!(preferred_track(&files), Some(&files[1]));
+        
+        let files = [PathBuf::from("/tmp/lyrics.eo.lrc"), PathBuf::from("/tmp/lyrics.en-orig.lrc")];
+        assert_eq!(preferred_track(&files), Some(&files[1]));
+        assert_eq!(preferred_track(&[]), None);
+    }
+    
+    #[test]
+    fn cleans_automatic_captions() {
+        let contents = "\
+[ar:Someone]
+[00:01.00][Music]
+[00:04.20]<00:00:04.200><c>Hello</c><00:00:04.700><c> darkness</c>
+[00:05.10]Hello darkness
+[00:07.00]♪ my old friend ♪
+[00:09.00]   
+[00:10.00][Applause]
+";
+        let lines = clean_lines(contents);
+        let lines: Vec<(&str, &str)> = lines.iter()
+            .map(|line| (line.timestamp.as_str(), line.text.as_str()))
+            .collect();
+        assert_eq!(lines, vec![
+            ("[00:04.20]", "Hello darkness"),
+            ("[00:07.00]", "my old friend"),
+        ]);
+    }
+    
+    #[test]
+    fn strips_inline_tags() {
+        assert_eq!(strip_markup("<c.colorE5E5E5>la</c> <00:00:01.000>la"), "la la");
+        assert_eq!(strip_markup("a > b"), "a > b");
+    }
+}
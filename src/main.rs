@@ -2,39 +2,279 @@
 
 use iced::{
     Element, Task,
-    widget::{button, column, container, image, row, scrollable, text, text_input, Image},
+    widget::{button, canvas, center, checkbox, column, container, image, opaque, pick_list, progress_bar, row, scrollable, slider, stack, text, text_input, Image},
     Length, Subscription,
     keyboard,
     event,
+    window,
 };
 use iced::futures::Stream;
 use iced::widget::text_input::Id as TextInputId;
 use iced::widget::scrollable::Id as ScrollableId;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
+use std::time::Duration;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tokio::sync::mpsc;
 
+mod api_server;
+mod audio_format;
+mod channel_subscriptions;
+mod chapters;
+mod diagnostics;
+mod download_error;
+mod ffmpeg_install;
+mod history;
+mod loudness;
+mod lyrics;
 mod macos_share;
+mod notifications;
+mod sponsorblock;
+mod thumbnail_cache;
+mod tray;
+mod trim;
 mod wifi_share;
+mod youtube_url;
+mod ytdlp_update;
 
-use wifi_share::ShareServer;
+use api_server::{ApiCommand, ApiReply, ApiRequest, ApiResponse, ApiServer};
+use audio_format::AudioFormat;
+use channel_subscriptions::{ChannelSubscription, SeenItems};
+use download_error::{DownloadError, SuggestedAction};
+use history::{DownloadHistory, SearchHistory};
+use loudness::LoudnessMode;
+use lyrics::LyricsOptions;
+use sponsorblock::SponsorBlock;
+use tray::{Tray, TrayEvent};
+use wifi_share::{QrImage, ShareServer};
+use youtube_url::YouTubeLink;
+use ytdlp_update::UpdateChannel;
+
+// Maximum number of yt-dlp processes running at the same time
+const MAX_CONCURRENT_DOWNLOADS: usize = 3;
+
+// Downloads queued from subscriptions listed in the subscriptions view
+const MAX_RECENT_SUBSCRIPTION_DOWNLOADS: usize = 20;
+
+// Bumped when a saved setting needs migrating on load
+const CONFIG_VERSION: u32 = 1;
 
 // Config for persistent settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 struct Config {
+    #[serde(default)]
+    config_version: u32, // 0 for files saved before versioning
     download_directory: Option<PathBuf>,
     browser_for_cookies: Option<String>, // chrome, firefox, safari, etc.
+    browser_profile: Option<String>, // profile name or path within the browser
+    cookies_file: Option<PathBuf>, // Netscape cookies.txt, used instead of the browser
+    wifi_ssid: Option<String>, // network advertised by the Wi-Fi join QR code
+    #[serde(skip)]
+    wifi_password: Option<String>, // kept in memory only, never written to config.json
+    ytdlp_channel: UpdateChannel, // release channel used for installs and updates
+    ffmpeg_path: Option<PathBuf>, // user-chosen ffmpeg binary or directory
+    retry_strategies: Vec<RetryStrategy>, // tried in order until a download succeeds
+    retry_base_delay_secs: u64, // wait before the first retry, doubled for each one after
+    notifications_enabled: bool, // desktop notification when each download finishes or fails
+    minimize_to_tray: bool, // closing the window keeps Nightingale running in the tray
+    key_bindings: KeyBindings,
+    bulk_filename_template: String, // filename for each result queued from the bulk bar
+    sponsorblock_enabled: bool, // cut marked non-music segments out of downloads
+    sponsorblock_api_url: String, // SponsorBlock server, or a local mirror
+    sponsorblock_categories: Vec<String>, // segment categories to remove
+    loudness_mode: LoudnessMode, // normalize or tag each download after it finishes
+    loudness_target_lufs: f64, // integrated loudness normalized tracks are brought to
+    lyrics_save_lrc: bool, // last choice in the Save As dialog, used as the next default
+    lyrics_embed: bool, // last choice in the Save As dialog, used as the next default
+    channel_subscriptions: Vec<ChannelSubscription>, // playlists and channels checked for new uploads
+    subscription_check_mins: u64, // how often each subscription is re-listed
+    clipboard_watch: bool, // offer to search or download YouTube links when they're copied
+    api_enabled: bool, // serve the JSON API on localhost
+    api_port: u16,
+    api_token: String, // callers must send this; generated when the API is first enabled
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            config_version: CONFIG_VERSION,
             download_directory: None,
-            browser_for_cookies: Some("safari".to_string()), // Default to Safari on macOS
+            browser_for_cookies: None,
+            browser_profile: None,
+            cookies_file: None,
+            wifi_ssid: None,
+            wifi_password: None,
+            ytdlp_channel: UpdateChannel::default(),
+            ffmpeg_path: None,
+            retry_strategies: RetryStrategy::defaults(),
+            retry_base_delay_secs: 5,
+            notifications_enabled: true,
+            minimize_to_tray: true,
+            key_bindings: KeyBindings::default(),
+            bulk_filename_template: "{title}".to_string(),
+            sponsorblock_enabled: false,
+            sponsorblock_api_url: sponsorblock::DEFAULT_API_URL.to_string(),
+            sponsorblock_categories: sponsorblock::CATEGORIES.iter().map(|(id, _)| id.to_string()).collect(),
+            loudness_mode: LoudnessMode::Off,
+            loudness_target_lufs: -14.0,
+            lyrics_save_lrc: false,
+            lyrics_embed: false,
+            channel_subscriptions: Vec::new(),
+            subscription_check_mins: 60,
+            clipboard_watch: false,
+            api_enabled: false,
+            api_port: 8765,
+            api_token: String::new(),
+        }
+    }
+}
+
+// A yt-dlp player client and format selection to try a download with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RetryStrategy {
+    player_client: String,
+    format: Option<String>, // yt-dlp -f selector, or yt-dlp's default when unset
+}
+
+impl Default for RetryStrategy {
+    fn default() -> Self {
+        Self {
+            player_client: "ios,android".to_string(),
+            format: None,
+        }
+    }
+}
+
+impl RetryStrategy {
+    fn defaults() -> Vec<Self> {
+        vec![
+            Self::default(),
+            Self {
+                player_client: "web".to_string(),
+                format: None,
+            },
+            Self {
+                player_client: "tv".to_string(),
+                format: Some("bestaudio[ext=m4a]/bestaudio".to_string()),
+            },
+            Self {
+                player_client: "mweb".to_string(),
+                format: Some("18/best".to_string()),
+            },
+        ]
+    }
+    
+    fn describe(&self) -> String {
+        match &self.format {
+            Some(format) if !format.is_empty() => format!("player_client={}, format={}", self.player_client, format),
+            _ => format!("player_client={}", self.player_client),
+        }
+    }
+}
+
+// Actions on the selected result that can be bound to a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyAction {
+    Next,
+    Previous,
+    Download,
+    OpenInBrowser,
+    ShowLogs,
+    Share,
+    ShowShortcuts,
+}
+
+impl KeyAction {
+    const ALL: [KeyAction; 7] = [
+        KeyAction::Next,
+        KeyAction::Previous,
+        KeyAction::Download,
+        KeyAction::OpenInBrowser,
+        KeyAction::ShowLogs,
+        KeyAction::Share,
+        KeyAction::ShowShortcuts,
+    ];
+    
+    fn label(&self) -> &'static str {
+        match self {
+            KeyAction::Next => "Next result",
+            KeyAction::Previous => "Previous result",
+            KeyAction::Download => "Download selected",
+            KeyAction::OpenInBrowser => "Open in browser",
+            KeyAction::ShowLogs => "Show download logs",
+            KeyAction::Share => "Share downloaded file",
+            KeyAction::ShowShortcuts => "Show shortcuts",
+        }
+    }
+}
+
+// Key for each action, as a character ("j", "?") or a named key ("Enter", "Space")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct KeyBindings {
+    next: String,
+    previous: String,
+    download: String,
+    open_in_browser: String,
+    show_logs: String,
+    share: String,
+    show_shortcuts: String,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            next: "j".to_string(),
+            previous: "k".to_string(),
+            download: "Enter".to_string(),
+            open_in_browser: "o".to_string(),
+            show_logs: "l".to_string(),
+            share: "s".to_string(),
+            show_shortcuts: "?".to_string(),
+        }
+    }
+}
+
+impl KeyBindings {
+    fn get(&self, action: KeyAction) -> &str {
+        match action {
+            KeyAction::Next => &self.next,
+            KeyAction::Previous => &self.previous,
+            KeyAction::Download => &self.download,
+            KeyAction::OpenInBrowser => &self.open_in_browser,
+            KeyAction::ShowLogs => &self.show_logs,
+            KeyAction::Share => &self.share,
+            KeyAction::ShowShortcuts => &self.show_shortcuts,
+        }
+    }
+    
+    fn get_mut(&mut self, action: KeyAction) -> &mut String {
+        match action {
+            KeyAction::Next => &mut self.next,
+            KeyAction::Previous => &mut self.previous,
+            KeyAction::Download => &mut self.download,
+            KeyAction::OpenInBrowser => &mut self.open_in_browser,
+            KeyAction::ShowLogs => &mut self.show_logs,
+            KeyAction::Share => &mut self.share,
+            KeyAction::ShowShortcuts => &mut self.show_shortcuts,
         }
     }
+    
+    // The action bound to a pressed key, if any
+    fn action_for(&self, key: &keyboard::Key) -> Option<KeyAction> {
+        let name = match key {
+            keyboard::Key::Character(c) => c.to_string(),
+            keyboard::Key::Named(named) => format!("{:?}", named),
+            keyboard::Key::Unidentified => return None,
+        };
+        
+        KeyAction::ALL.into_iter().find(|action| {
+            let binding = self.get(*action).trim();
+            !binding.is_empty() && binding.eq_ignore_ascii_case(&name)
+        })
+    }
 }
 
 impl Config {
@@ -42,7 +282,23 @@ impl Config {
         if let Some(config_dir) = dirs::config_dir() {
             let config_file = config_dir.join("nightingale").join("config.json");
             if let Ok(contents) = std::fs::read_to_string(&config_file) {
-                if let Ok(config) = serde_json::from_str(&contents) {
+                if let Ok(mut config) = serde_json::from_str::<Config>(&contents) {
+                    // Older versions saved the Wi-Fi password in plain text; rewrite the file without it
+                    let mut migrated = contents.contains("\"wifi_password\"");
+                    if config.config_version < 1 {
+                        // Safari used to be the default browser for cookies, so it's in most old configs
+                        // without anyone choosing it; reading it needs Full Disk Access on macOS
+                        if config.browser_for_cookies.as_deref() == Some("safari") {
+                            config.browser_for_cookies = None;
+                        }
+                        config.config_version = CONFIG_VERSION;
+                        migrated = true;
+                    }
+                    if migrated {
+                        if let Err(e) = config.save() {
+                            eprintln!("Failed to migrate config: {}", e);
+                        }
+                    }
                     return config;
                 }
             }
@@ -65,12 +321,49 @@ impl Config {
         }
         Ok(())
     }
+    
+    // yt-dlp arguments for authenticating with cookies, if configured
+    fn cookie_args(&self) -> Vec<String> {
+        if let Some(file) = &self.cookies_file {
+            return vec!["--cookies".to_string(), file.to_string_lossy().to_string()];
+        }
+        
+        match &self.browser_for_cookies {
+            // e.g. Safari, which can only be read on macOS, in a config copied from a Mac
+            Some(browser) if !COOKIE_BROWSERS.contains(&browser.as_str()) => Vec::new(),
+            Some(browser) => {
+                let spec = match self.browser_profile.as_deref().filter(|p| !p.trim().is_empty()) {
+                    Some(profile) => format!("{}:{}", browser, profile.trim()),
+                    None => browser.clone(),
+                };
+                vec!["--cookies-from-browser".to_string(), spec]
+            }
+            None => Vec::new(),
+        }
+    }
+}
+
+// Browsers yt-dlp can read cookies from
+const COOKIE_BROWSERS: &[&str] = if cfg!(target_os = "macos") {
+    &["safari", "chrome", "chromium", "brave", "edge", "firefox", "opera", "vivaldi"]
+} else {
+    &["chrome", "chromium", "brave", "edge", "firefox", "opera", "vivaldi"]
+};
+
+// Label for "don't use cookies" in the browser picker
+const NO_COOKIE_BROWSER: &str = "none";
+
+const AGE_RESTRICTED_HINT: &str = "This video is age-restricted. Choose a browser to read cookies from in Settings, then try again.";
+
+// Directory where Nightingale installs its own copies of yt-dlp and ffmpeg
+fn get_local_bin_dir() -> PathBuf {
+    let home = dirs::home_dir().expect("Could not find home directory");
+    home.join(".local").join("bin")
 }
 
 // Helper function to get yt-dlp binary path in local directory
 fn get_ytdlp_path() -> PathBuf {
-    let home = dirs::home_dir().expect("Could not find home directory");
-    home.join(".local").join("bin").join("yt-dlp")
+    get_local_bin_dir().join("yt-dlp")
 }
 
 // Check if yt-dlp is installed and executable
@@ -113,9 +406,16 @@ fn fix_ytdlp_shebang() -> Result<(), String> {
             })
             .unwrap_or_else(|| "/usr/bin/env python3".to_string());
         
-        let contents = std::fs::read_to_string(&ytdlp_path)
+        let bytes = std::fs::read(&ytdlp_path)
             .map_err(|e| format!("Failed to read yt-dlp: {}", e))?;
         
+        // Standalone builds (e.g. yt-dlp_macos) are native binaries with no shebang
+        if !bytes.starts_with(b"#!/") {
+            return Ok(());
+        }
+        
+        let contents = String::from_utf8_lossy(&bytes);
+        
         // Replace any python shebang with the one from PATH
         if contents.starts_with("#!/") {
             if let Some(newline_pos) = contents.find('\n') {
@@ -130,57 +430,13 @@ fn fix_ytdlp_shebang() -> Result<(), String> {
     Ok(())
 }
 
-// Download yt-dlp nightly binary for the current platform
-async fn download_ytdlp() -> Result<(), String> {
-    let ytdlp_path = get_ytdlp_path();
-    
-    // Create .local/bin directory if it doesn't exist
-    if let Some(parent) = ytdlp_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create directory: {}", e))?;
-    }
-    
-    // Determine platform-specific download URL
-    let download_url = if cfg!(target_os = "macos") {
-        "https://github.com/yt-dlp/yt-dlp/releases/latest/download/yt-dlp_macos"
-    } else if cfg!(target_os = "linux") {
-        "https://github.com/yt-dlp/yt-dlp/releases/latest/download/yt-dlp"
-    } else {
-        return Err("Unsupported platform".to_string());
-    };
-    
-    // Download the binary
-    let client = reqwest::Client::new();
-    let response = client
-        .get(download_url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to download yt-dlp: {}", e))?;
-    
-    let bytes = response
-        .bytes()
-        .await
-        .map_err(|e| format!("Failed to read download: {}", e))?;
-    
-    // Write to file
-    std::fs::write(&ytdlp_path, bytes)
-        .map_err(|e| format!("Failed to write yt-dlp binary: {}", e))?;
-    
-    // Make executable (Unix-like systems)
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = std::fs::metadata(&ytdlp_path)
-            .map_err(|e| format!("Failed to get file metadata: {}", e))?
-            .permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(&ytdlp_path, perms)
-            .map_err(|e| format!("Failed to set executable permissions: {}", e))?;
-    }
-    
+// Download the latest yt-dlp release from a channel, returning the installed version
+async fn download_ytdlp(channel: UpdateChannel) -> Result<String, String> {
+    let release = ytdlp_update::fetch_latest_release(channel).await?;
+    ytdlp_update::install_release(&release, &get_ytdlp_path()).await?;
     fix_ytdlp_shebang()?;
     
-    Ok(())
+    Ok(release.version)
 }
 
 // Helper function to find yt-dlp binary
@@ -208,33 +464,64 @@ fn find_ytdlp() -> String {
     local_path.to_string_lossy().to_string()
 }
 
-// Helper function to find ffmpeg location
-fn find_ffmpeg() -> Option<String> {
-    let possible_paths = vec![
-        "/opt/homebrew/bin/ffmpeg",      // Homebrew (Apple Silicon)
-        "/usr/local/bin/ffmpeg",          // Homebrew (Intel)
-        "/usr/bin/ffmpeg",                // System install
-    ];
-    
-    for path in possible_paths {
-        if std::path::Path::new(path).exists() {
-            // Return the directory containing ffmpeg
-            return Some(std::path::Path::new(path).parent()?.to_str()?.to_string());
+// Helper function to find ffmpeg location, suitable for --ffmpeg-location
+fn find_ffmpeg(custom_path: Option<&std::path::Path>) -> Option<String> {
+    // A user-chosen location wins, whether it's the binary or its directory
+    if let Some(path) = custom_path {
+        if path.exists() {
+            return Some(path.to_string_lossy().to_string());
         }
     }
     
-    None
+    let mut search_dirs = vec![get_local_bin_dir()];
+    
+    if let Some(path_var) = std::env::var_os("PATH") {
+        search_dirs.extend(std::env::split_paths(&path_var));
+    }
+    
+    // Apps launched from Finder or a desktop launcher often get a minimal PATH
+    search_dirs.extend([
+        PathBuf::from("/opt/homebrew/bin"),      // Homebrew (Apple Silicon)
+        PathBuf::from("/usr/local/bin"),          // Homebrew (Intel)
+        PathBuf::from("/usr/bin"),                // System install
+    ]);
+    
+    search_dirs
+        .into_iter()
+        .find(|dir| dir.join("ffmpeg").is_file())
+        .map(|dir| dir.to_string_lossy().to_string())
 }
 
-async fn load_thumbnail(url: &str) -> Result<image::Handle, String> {
-    let bytes = reqwest::get(url)
-        .await
-        .map_err(|e| format!("Failed to download thumbnail: {}", e))?
-        .bytes()
-        .await
-        .map_err(|e| format!("Failed to read thumbnail bytes: {}", e))?;
+#[derive(Debug, Clone, Default)]
+struct FfmpegVersions {
+    ffmpeg: Option<String>,
+    ffprobe: Option<String>,
+}
+
+async fn load_ffmpeg_versions(location: Option<String>) -> FfmpegVersions {
+    let Some(location) = location else {
+        return FfmpegVersions::default();
+    };
     
-    Ok(image::Handle::from_bytes(bytes))
+    let (ffmpeg, ffprobe) = ffmpeg_install::binaries(std::path::Path::new(&location));
+    FfmpegVersions {
+        ffmpeg: ffmpeg_install::tool_version(ffmpeg).await,
+        ffprobe: ffmpeg_install::tool_version(ffprobe).await,
+    }
+}
+
+fn thumbnail_task(video: &VideoResult) -> Task<Message> {
+    let url = video.thumbnail.clone();
+    let video_id = video.video_id.clone();
+    Task::perform(
+        async move {
+            let result = thumbnail_cache::load(video_id.clone(), url)
+                .await
+                .map(image::Handle::from_bytes);
+            (video_id, result)
+        },
+        |(video_id, result)| Message::ThumbnailLoaded(video_id, result),
+    )
 }
 
 fn clean_filename(title: &str) -> String {
@@ -267,15 +554,165 @@ fn clean_filename(title: &str) -> String {
     cleaned.trim().to_string()
 }
 
+// Fill in a bulk filename template: {title}, {channel}, {id}, and {index} (position in the selection)
+fn render_filename_template(template: &str, video: &VideoResult, index: usize) -> String {
+    let filename = template
+        .replace("{title}", &clean_filename(&video.title))
+        .replace("{channel}", &video.channel)
+        .replace("{id}", &video.video_id)
+        .replace("{index}", &format!("{:02}", index))
+        .replace(['/', '\\'], "-");
+    
+    let filename = filename.trim();
+    if filename.is_empty() {
+        clean_filename(&video.title)
+    } else {
+        filename.to_string()
+    }
+}
+
+// Post-processors that run after the download finishes, as named in yt-dlp's output
+const POSTPROCESSOR_TAGS: &[&str] = &[
+    "[ExtractAudio]",
+    "[Metadata]",
+    "[EmbedThumbnail]",
+    "[ModifyChapters]",
+    "[SplitChapters]",
+    "[FixupM4a]",
+    "[FixupM3u8]",
+    "[FixupTimestamp]",
+    "[FixupDuration]",
+];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+enum DownloadStage {
+    #[default]
+    Downloading,
+    Converting, // ffmpeg is extracting/encoding the audio
+    PostProcessing, // metadata, thumbnails and other fixups
+}
+
+// Progress of a single download as reported by yt-dlp's progress template
+#[derive(Debug, Clone, Default)]
+struct DownloadProgress {
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>, // exact or estimated size
+    speed: Option<f64>, // bytes per second
+    eta: Option<u64>, // seconds
+    fragments: Option<(u64, u64)>, // (index, count) for fragmented downloads
+    stage: DownloadStage,
+}
+
+impl DownloadProgress {
+    // Parse a line produced by PROGRESS_TEMPLATE
+    fn parse(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.strip_prefix("download:")?.split('/').collect();
+        if fields.len() != 7 {
+            return None;
+        }
+        
+        // yt-dlp prints "NA" for fields it doesn't know
+        let int = |value: &str| value.parse::<f64>().ok().map(|v| v as u64);
+        
+        Some(Self {
+            downloaded_bytes: int(fields[0])?,
+            total_bytes: int(fields[1]).or_else(|| int(fields[2])).filter(|t| *t > 0),
+            speed: fields[3].parse::<f64>().ok(),
+            eta: int(fields[4]),
+            fragments: int(fields[5]).zip(int(fields[6])).filter(|(_, count)| *count > 0),
+            stage: DownloadStage::Downloading,
+        })
+    }
+    
+    // Percentage complete, from the byte count or, failing that, the fragment count
+    fn percent(&self) -> Option<f32> {
+        if let Some(total) = self.total_bytes {
+            return Some((self.downloaded_bytes as f32 / total as f32 * 100.0).min(100.0));
+        }
+        self.fragments
+            .map(|(index, count)| (index as f32 / count as f32 * 100.0).min(100.0))
+    }
+    
+    // e.g. "Downloading 3.2 MB/s · 0:12 left · 4.1 MB of 9.8 MB"
+    fn status_text(&self) -> String {
+        match self.stage {
+            DownloadStage::Converting => return "Converting…".to_string(),
+            DownloadStage::PostProcessing => return "Finishing up…".to_string(),
+            DownloadStage::Downloading => {}
+        }
+        
+        let mut parts = vec![match self.speed {
+            Some(speed) => format!("Downloading {}/s", format_bytes(speed as u64)),
+            None => "Downloading".to_string(),
+        }];
+        
+        if let Some(eta) = self.eta {
+            parts.push(format!("{}:{:02} left", eta / 60, eta % 60));
+        }
+        
+        if let Some(total) = self.total_bytes {
+            parts.push(format!("{} of {}", format_bytes(self.downloaded_bytes), format_bytes(total)));
+        } else if self.downloaded_bytes > 0 {
+            parts.push(format_bytes(self.downloaded_bytes));
+        }
+        
+        if let (None, Some((index, count))) = (self.total_bytes, self.fragments) {
+            parts.push(format!("fragment {}/{}", index, count));
+        }
+        
+        parts.join(" · ")
+    }
+}
+
+const PROGRESS_TEMPLATE: &str = "download:%(progress.downloaded_bytes)s/%(progress.total_bytes)s/%(progress.total_bytes_estimate)s/%(progress.speed)s/%(progress.eta)s/%(progress.fragment_index)s/%(progress.fragment_count)s";
+
+fn format_bytes(bytes: u64) -> String {
+    let bytes = bytes as f64;
+    if bytes >= 1_000_000_000.0 {
+        format!("{:.1} GB", bytes / 1_000_000_000.0)
+    } else if bytes >= 1_000_000.0 {
+        format!("{:.1} MB", bytes / 1_000_000.0)
+    } else if bytes >= 1_000.0 {
+        format!("{:.0} KB", bytes / 1_000.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
 // Message enum for download updates
 #[derive(Debug, Clone)]
 enum DownloadUpdate {
-    Progress(f32),
+    Progress(DownloadProgress),
+    Stage(DownloadStage),
     Log(String),
-    Completed(Result<String, String>),
+    Completed(Result<PathBuf, DownloadError>), // final path of the audio file
+}
+
+// Turn a line of yt-dlp output into progress or stage updates
+fn report_output_line(line: &str, tx: &mpsc::UnboundedSender<DownloadUpdate>) {
+    if let Some(progress) = DownloadProgress::parse(line) {
+        let _ = tx.send(DownloadUpdate::Progress(progress));
+    } else if line.starts_with("[ExtractAudio]") {
+        let _ = tx.send(DownloadUpdate::Stage(DownloadStage::Converting));
+    } else if POSTPROCESSOR_TAGS.iter().any(|tag| line.starts_with(tag)) {
+        let _ = tx.send(DownloadUpdate::Stage(DownloadStage::PostProcessing));
+    }
+}
+
+// Per-download settings taken from the config when the download starts
+#[derive(Debug, Clone)]
+struct DownloadOptions {
+    ffmpeg_location: Option<String>,
+    cookie_args: Vec<String>,
+    retry_strategies: Vec<RetryStrategy>,
+    retry_base_delay_secs: u64,
+    download_section: Option<String>, // only download this part of the video
+    sponsorblock: Option<SponsorBlock>, // segments to cut out, when enabled
+    split_chapters: bool, // chapter times must still match the file afterwards
+    audio_format: AudioFormat,
 }
 
-fn download_mp3_stream_with_filename(video_id: String, download_dir: PathBuf, filename: String) -> impl Stream<Item = DownloadUpdate> {
+fn download_mp3_stream_with_filename(video_id: String, download_dir: PathBuf, filename: String, mut options: DownloadOptions) -> impl Stream<Item = DownloadUpdate> {
     let (tx, rx) = mpsc::unbounded_channel();
     
     tokio::spawn(async move {
@@ -286,94 +723,82 @@ fn download_mp3_stream_with_filename(video_id: String, download_dir: PathBuf, fi
             .to_string_lossy()
             .to_string();
         
-        use tokio::io::{AsyncBufReadExt, BufReader};
-        use tokio::process::Command;
-        
-        let ytdlp_path = find_ytdlp();
+        if options.ffmpeg_location.is_none() {
+            let _ = tx.send(DownloadUpdate::Log(
+                "Warning: ffmpeg not found, MP3 conversion will fail. Install it from Settings.".to_string()
+            ));
+        }
         
-        let result = async {
-            let mut cmd = Command::new(&ytdlp_path);
-            cmd.arg("-x")
-                .arg("--extractor-args")
-                .arg("youtube:player_client=ios,android")
-                .arg("--audio-format")
-                .arg("mp3")
-                .arg("--no-playlist")
-                .arg("--verbose");
-            
-            if let Some(ffmpeg_dir) = find_ffmpeg() {
-                cmd.arg("--ffmpeg-location").arg(&ffmpeg_dir);
-            }
-            
-            cmd.arg("--extractor-retries")
-                .arg("5")
-                .arg("--fragment-retries")
-                .arg("5")
-                .arg("--newline")
-                .arg("--progress-template")
-                .arg("download:%(progress.downloaded_bytes)s/%(progress.total_bytes)s")
-                .arg("-o")
-                .arg(&output_template)
-                .arg(&url)
-                .current_dir(&download_dir)
-                .stdout(std::process::Stdio::piped())
-                .stderr(std::process::Stdio::piped());
-            
-            let mut child = cmd.spawn()
-                .map_err(|e| format!("Failed to run yt-dlp (is it installed?): {}", e))?;
-            
-            let _ = tx.send(DownloadUpdate::Progress(0.0));
-            
-            let stdout_handle = child.stdout.take();
-            let stderr_handle = child.stderr.take();
-            
-            let tx_stderr = tx.clone();
-            if let Some(stderr) = stderr_handle {
-                tokio::spawn(async move {
-                    let reader = BufReader::new(stderr);
-                    let mut lines = reader.lines();
-                    while let Ok(Some(line)) = lines.next_line().await {
-                        let _ = tx_stderr.send(DownloadUpdate::Log(line));
+        // Look the segments up first so the log shows what will be cut; yt-dlp only gets
+        // the removal flags when there is something to remove
+        if let Some(sponsorblock) = options.sponsorblock.take() {
+            if options.download_section.is_some() {
+                let _ = tx.send(DownloadUpdate::Log(
+                    "SponsorBlock: skipped, only part of the video is being downloaded".to_string()
+                ));
+            } else if options.split_chapters {
+                let _ = tx.send(DownloadUpdate::Log(
+                    "SponsorBlock: skipped, removing segments would shift the chapters being split".to_string()
+                ));
+            } else {
+                match sponsorblock.fetch_segments(&video_id).await {
+                    Ok(segments) if segments.is_empty() => {
+                        let _ = tx.send(DownloadUpdate::Log("SponsorBlock: no segments to remove".to_string()));
                     }
-                });
-            }
-            
-            if let Some(stdout) = stdout_handle {
-                let reader = BufReader::new(stdout);
-                let mut lines = reader.lines();
-                
-                while let Ok(Some(line)) = lines.next_line().await {
-                    let _ = tx.send(DownloadUpdate::Log(line.clone()));
-                    
-                    if line.starts_with("download:") {
-                        if let Some(progress_part) = line.strip_prefix("download:") {
-                            let parts: Vec<&str> = progress_part.split('/').collect();
-                            if parts.len() == 2 {
-                                if let (Ok(downloaded), Ok(total)) = (
-                                    parts[0].parse::<f32>(),
-                                    parts[1].parse::<f32>(),
-                                ) {
-                                    if total > 0.0 {
-                                        let percent = (downloaded / total * 100.0).min(100.0);
-                                        let _ = tx.send(DownloadUpdate::Progress(percent));
-                                    }
-                                }
-                            }
+                    Ok(segments) => {
+                        for segment in &segments {
+                            let _ = tx.send(DownloadUpdate::Log(format!(
+                                "SponsorBlock: removing {} {} - {}",
+                                segment.category,
+                                trim::format_time(segment.range.0),
+                                trim::format_time(segment.range.1),
+                            )));
                         }
+                        options.sponsorblock = Some(sponsorblock);
+                    }
+                    Err(e) => {
+                        let _ = tx.send(DownloadUpdate::Log(format!("SponsorBlock: {}; keeping the whole video", e)));
                     }
                 }
             }
+        }
+        
+        let strategies = if options.retry_strategies.is_empty() {
+            vec![RetryStrategy::default()]
+        } else {
+            options.retry_strategies.clone()
+        };
+        
+        let mut result: Result<PathBuf, DownloadError> = Err(DownloadError::Other("No download attempts were made".to_string()));
+        
+        for (attempt, strategy) in strategies.iter().enumerate() {
+            if attempt > 0 {
+                // Exponential backoff: base, 2x base, 4x base, ...
+                let delay = options.retry_base_delay_secs.saturating_mul(1 << (attempt - 1).min(16));
+                let _ = tx.send(DownloadUpdate::Log(format!("Retrying in {}s...", delay)));
+                tokio::time::sleep(Duration::from_secs(delay)).await;
+            }
             
-            let output = child.wait().await
-                .map_err(|e| format!("Failed to wait for yt-dlp: {}", e))?;
+            let _ = tx.send(DownloadUpdate::Log(format!(
+                "Attempt {}/{}: {}",
+                attempt + 1,
+                strategies.len(),
+                strategy.describe(),
+            )));
             
-            if !output.success() {
-                let error_msg = format!("yt-dlp failed with exit code: {:?}. Check logs for details.", output.code());
-                return Err(error_msg);
-            }
+            result = run_download_attempt(&url, &output_template, &download_dir, &options, strategy, &tx).await;
             
-            Ok(format!("Downloaded successfully to {}", download_dir.display()))
-        }.await;
+            let next = strategies.get(attempt + 1);
+            match (&result, next) {
+                (Err(e), Some(next)) if e.is_retryable(
+                    next.player_client != strategy.player_client,
+                    next.format != strategy.format,
+                ) => {
+                    let _ = tx.send(DownloadUpdate::Log(format!("Attempt {} failed: {}", attempt + 1, e)));
+                }
+                _ => break,
+            }
+        }
         
         let _ = tx.send(DownloadUpdate::Completed(result));
     });
@@ -381,15 +806,127 @@ fn download_mp3_stream_with_filename(video_id: String, download_dir: PathBuf, fi
     UnboundedReceiverStream::new(rx)
 }
 
-// Check if input is a YouTube URL
-fn is_youtube_url(input: &str) -> bool {
-    input.contains("youtube.com/") || input.contains("youtu.be/")
+// Run yt-dlp once with the given player client and format selection,
+// returning the path yt-dlp reports for the finished file
+async fn run_download_attempt(
+    url: &str,
+    output_template: &str,
+    download_dir: &std::path::Path,
+    options: &DownloadOptions,
+    strategy: &RetryStrategy,
+    tx: &mpsc::UnboundedSender<DownloadUpdate>,
+) -> Result<PathBuf, DownloadError> {
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::process::Command;
+    
+    let ytdlp_path = find_ytdlp();
+    
+    let mut cmd = Command::new(&ytdlp_path);
+    cmd.arg("-x")
+        .arg("--extractor-args")
+        .arg(format!("youtube:player_client={}", strategy.player_client))
+        .arg("--audio-format")
+        .arg(options.audio_format.extension())
+        .arg("--no-playlist")
+        .arg("--verbose");
+    
+    if let Some(format) = strategy.format.as_deref().filter(|f| !f.is_empty()) {
+        cmd.arg("-f").arg(format);
+    }
+    
+    cmd.args(&options.cookie_args);
+    
+    if let Some(section) = &options.download_section {
+        cmd.arg("--download-sections").arg(section);
+    }
+    
+    if let Some(sponsorblock) = &options.sponsorblock {
+        cmd.arg("--sponsorblock-remove")
+            .arg(sponsorblock.categories.join(","))
+            .arg("--sponsorblock-api")
+            .arg(&sponsorblock.api_url);
+    }
+    
+    if let Some(ffmpeg_location) = &options.ffmpeg_location {
+        cmd.arg("--ffmpeg-location").arg(ffmpeg_location);
+    }
+    
+    cmd.arg("--extractor-retries")
+        .arg("5")
+        .arg("--fragment-retries")
+        .arg("5")
+        .arg("--newline")
+        .arg("--progress-template")
+        .arg(PROGRESS_TEMPLATE)
+        // --print makes yt-dlp quiet, which moves its log and progress to stderr
+        // and leaves stdout with just the final path
+        .arg("--print")
+        .arg("after_move:filepath")
+        .arg("--progress")
+        .arg("-o")
+        .arg(output_template)
+        .arg(url)
+        .current_dir(download_dir)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    
+    let mut child = cmd.spawn()
+        .map_err(|e| DownloadError::Other(format!("Failed to run yt-dlp (is it installed?): {}", e)))?;
+    
+    let _ = tx.send(DownloadUpdate::Progress(DownloadProgress::default()));
+    
+    let stdout_handle = child.stdout.take();
+    let stderr_handle = child.stderr.take();
+    
+    // Keep stderr around to work out why a download failed
+    let tx_stderr = tx.clone();
+    let stderr_task = stderr_handle.map(|stderr| {
+        tokio::spawn(async move {
+            let reader = BufReader::new(stderr);
+            let mut lines = reader.lines();
+            let mut collected = Vec::new();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let _ = tx_stderr.send(DownloadUpdate::Log(line.clone()));
+                report_output_line(&line, &tx_stderr);
+                collected.push(line);
+            }
+            collected
+        })
+    });
+    
+    let mut output_path = None;
+    
+    if let Some(stdout) = stdout_handle {
+        let reader = BufReader::new(stdout);
+        let mut lines = reader.lines();
+        
+        while let Ok(Some(line)) = lines.next_line().await {
+            let _ = tx.send(DownloadUpdate::Log(line.clone()));
+            
+            if std::path::Path::new(&line).is_absolute() {
+                output_path = Some(PathBuf::from(line));
+            } else {
+                report_output_line(&line, tx);
+            }
+        }
+    }
+    
+    let output = child.wait().await
+        .map_err(|e| DownloadError::Other(format!("Failed to wait for yt-dlp: {}", e)))?;
+    
+    if !output.success() {
+        let stderr_lines = match stderr_task {
+            Some(task) => task.await.unwrap_or_default(),
+            None => Vec::new(),
+        };
+        return Err(DownloadError::classify(stderr_lines.iter().map(String::as_str), output.code()));
+    }
+    
+    output_path.ok_or_else(|| DownloadError::Other("yt-dlp did not report where it saved the file".to_string()))
 }
 
-
-
 // Get video info from URL using yt-dlp
-async fn get_video_info_from_url(url: &str) -> Result<Vec<VideoResult>, String> {
+async fn get_video_info_from_url(url: &str, cookie_args: &[String]) -> Result<Vec<VideoResult>, String> {
     use tokio::process::Command;
     
     let ytdlp_path = find_ytdlp();
@@ -397,6 +934,7 @@ async fn get_video_info_from_url(url: &str) -> Result<Vec<VideoResult>, String>
     let output = Command::new(&ytdlp_path)
         .arg("--dump-json")
         .arg("--flat-playlist")
+        .args(cookie_args)
         .arg(url)
         .output()
         .await
@@ -445,11 +983,9 @@ async fn get_video_info_from_url(url: &str) -> Result<Vec<VideoResult>, String>
             "Unknown views".to_string()
         };
         
-        let thumbnail = json["thumbnail"].as_str()
-            .or_else(|| json["thumbnails"].as_array()
-                .and_then(|t| t.first())
-                .and_then(|t| t["url"].as_str()))
-            .unwrap_or("").to_string();
+        let thumbnail = thumbnail_cache::best_thumbnail(&json["thumbnails"])
+            .or_else(|| json["thumbnail"].as_str().map(|url| url.to_string()))
+            .unwrap_or_default();
         
         if !video_id.is_empty() {
             results.push(VideoResult {
@@ -470,12 +1006,36 @@ async fn get_video_info_from_url(url: &str) -> Result<Vec<VideoResult>, String>
     }
 }
 
-async fn search_youtube(query: &str) -> Result<Vec<VideoResult>, String> {
-    // Check if input is a YouTube URL
-    if is_youtube_url(query) {
-        return get_video_info_from_url(query).await;
+async fn search_youtube(query: &str, cookie_args: &[String]) -> Result<Vec<VideoResult>, String> {
+    // Links go to yt-dlp in their canonical form; a results page link searches its terms
+    let terms = match YouTubeLink::parse(query) {
+        YouTubeLink::Search(terms) => terms,
+        link => return get_video_info_from_url(&link.url(), cookie_args).await,
+    };
+    
+    // An 11-character word like "Coldplay123" may be a bare video id or just search terms,
+    // so look it up alongside the search and put the video first if there is one
+    if youtube_url::is_video_id(&terms) {
+        let video_url = youtube_url::video_url(&terms);
+        let (video, results) = tokio::join!(
+            get_video_info_from_url(&video_url, cookie_args),
+            search_terms(&terms),
+        );
+        return match (video, results) {
+            (Ok(mut video), Ok(results)) => {
+                video.extend(results.into_iter().filter(|result| result.video_id != terms));
+                Ok(video)
+            }
+            (Ok(video), Err(_)) => Ok(video),
+            (Err(_), results) => results,
+        };
     }
     
+    search_terms(&terms).await
+}
+
+// Scrape YouTube's results page for a search
+async fn search_terms(query: &str) -> Result<Vec<VideoResult>, String> {
     let client = reqwest::Client::builder()
         .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
         .build()
@@ -543,10 +1103,8 @@ async fn search_youtube(query: &str) -> Result<Vec<VideoResult>, String> {
                     .unwrap_or("Unknown views")
                     .to_string();
                 
-                let thumbnail = video["thumbnail"]["thumbnails"][0]["url"]
-                    .as_str()
-                    .unwrap_or("")
-                    .to_string();
+                let thumbnail = thumbnail_cache::best_thumbnail(&video["thumbnail"]["thumbnails"])
+                    .unwrap_or_default();
                 
                 if !video_id.is_empty() {
                     results.push(VideoResult {
@@ -579,6 +1137,7 @@ async fn search_youtube(query: &str) -> Result<Vec<VideoResult>, String> {
 pub fn main() -> iced::Result {
     iced::application("Songbird - YouTube Search", Songbird::update, Songbird::view)
         .subscription(Songbird::subscription)
+        .exit_on_close_request(false)
         .theme(|_| iced::Theme::TokyoNightStorm)
         .run_with(Songbird::new)
 }
@@ -598,6 +1157,18 @@ impl VideoResult {
         format!("https://www.youtube.com/watch?v={}", self.video_id)
     }
     
+    // As returned from the API
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "video_id": self.video_id,
+            "title": self.title,
+            "channel": self.channel,
+            "duration": self.duration,
+            "views": self.views,
+            "url": self.url(),
+        })
+    }
+    
     fn calculate_score(&self, search_query: &str) -> i32 {
         let mut score = 0;
         
@@ -671,6 +1242,14 @@ impl VideoResult {
         score
     }
     
+    fn view_count(&self) -> Option<u64> {
+        parse_count(self.views.split_whitespace().next()?)
+    }
+    
+    fn duration_secs(&self) -> Option<u64> {
+        parse_clock(&self.duration)
+    }
+    
     fn parse_view_score(&self) -> Option<i32> {
         let views = self.views.to_lowercase();
         
@@ -702,35 +1281,279 @@ impl VideoResult {
     }
 }
 
-#[derive(Debug, Clone)]
-enum Message {
-    SearchInputChanged(String),
-    SearchPressed,
-    SearchCompleted(Result<Vec<VideoResult>, String>),
-    ThumbnailLoaded(String, Result<image::Handle, String>),
-    DownloadMp3(String), // video_id
-    DownloadProgress(String, f32), // video_id, progress (0-100)
-    DownloadLog(String, String), // video_id, log line
-    DownloadCompleted(String, Result<String, String>), // video_id, result message
-    OpenUrl(String), // url to open in browser
-    ToggleSettings, // Open/close settings view
-    PickDirectory, // Open native directory picker
-    DirectoryPicked(Option<PathBuf>), // Result from directory picker
-    ShowLogs(String), // video_id
-    CopyLogs(String), // video_id
-    CloseLogs,
-    ShowPlayerLogs,
-    CopyPlayerLogs,
-    ClosePlayerLogs,
-    KeyboardEvent(keyboard::Event), // Keyboard events
-    InstallYtDlp, // Install yt-dlp binary
-    YtDlpInstalled(Result<(), String>), // Result of installation
-    ShowRenameModal(String), // video_id
-    RenameFilenameChanged(String),
-    ConfirmDownload,
-    CancelRename,
-    ShareFile(String), // video_id
-    CloseShare,
+// Parse a count like "1.2M", "350K" or "1,234,567"
+fn parse_count(value: &str) -> Option<u64> {
+    let value = value.trim().replace(',', "").to_lowercase();
+    let (number, multiplier) = if let Some(n) = value.strip_suffix('b') {
+        (n, 1_000_000_000.0)
+    } else if let Some(n) = value.strip_suffix('m') {
+        (n, 1_000_000.0)
+    } else if let Some(n) = value.strip_suffix('k') {
+        (n, 1_000.0)
+    } else {
+        (value.as_str(), 1.0)
+    };
+    
+    number.parse::<f64>().ok().map(|n| (n * multiplier) as u64)
+}
+
+// Parse "h:mm:ss" or "m:ss" into seconds
+fn parse_clock(value: &str) -> Option<u64> {
+    value.trim().split(':').try_fold(0u64, |total, part| {
+        part.parse::<u64>().ok().map(|n| total * 60 + n)
+    })
+}
+
+// Parse a duration typed into the filter bar: "3:30", or a bare number of minutes
+fn parse_duration_input(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if value.contains(':') {
+        parse_clock(value)
+    } else {
+        value.parse::<f64>().ok().map(|minutes| (minutes * 60.0) as u64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ResultSort {
+    #[default]
+    Score,
+    Views,
+    Duration,
+    Title,
+}
+
+impl ResultSort {
+    const ALL: [ResultSort; 4] = [
+        ResultSort::Score,
+        ResultSort::Views,
+        ResultSort::Duration,
+        ResultSort::Title,
+    ];
+}
+
+impl std::fmt::Display for ResultSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ResultSort::Score => "Best match",
+            ResultSort::Views => "Most views",
+            ResultSort::Duration => "Shortest",
+            ResultSort::Title => "Title (A-Z)",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Client-side refinement of the search results; inputs are kept as typed
+#[derive(Debug, Clone, Default)]
+struct ResultFilter {
+    text: String, // matched against title and channel
+    exclude: String, // comma-separated keywords, e.g. "live, cover, remix"
+    min_views: String,
+    min_duration: String,
+    max_duration: String,
+    sort: ResultSort,
+}
+
+impl ResultFilter {
+    fn is_active(&self) -> bool {
+        [&self.text, &self.exclude, &self.min_views, &self.min_duration, &self.max_duration]
+            .iter()
+            .any(|value| !value.trim().is_empty())
+    }
+    
+    fn matches(&self, video: &VideoResult) -> bool {
+        let title = video.title.to_lowercase();
+        let channel = video.channel.to_lowercase();
+        
+        let text = self.text.trim().to_lowercase();
+        if !text.is_empty() && !title.contains(&text) && !channel.contains(&text) {
+            return false;
+        }
+        
+        let excluded = self.exclude
+            .split(',')
+            .map(|keyword| keyword.trim().to_lowercase())
+            .filter(|keyword| !keyword.is_empty())
+            .any(|keyword| title.contains(&keyword));
+        if excluded {
+            return false;
+        }
+        
+        // Results with an unknown view count or duration can't be ruled out by a minimum or range
+        if let Some(min_views) = parse_count(&self.min_views) {
+            if video.view_count().is_some_and(|views| views < min_views) {
+                return false;
+            }
+        }
+        
+        if let Some(duration) = video.duration_secs() {
+            if parse_duration_input(&self.min_duration).is_some_and(|min| duration < min) {
+                return false;
+            }
+            if parse_duration_input(&self.max_duration).is_some_and(|max| duration > max) {
+                return false;
+            }
+        }
+        
+        true
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    SearchInputChanged(String),
+    SearchPressed,
+    HistorySelected(String), // Run a search from the history dropdown
+    ToggleHistoryPin(String),
+    RemoveHistoryEntry(String),
+    SearchCompleted(Result<Vec<VideoResult>, String>),
+    ThumbnailLoaded(String, Result<image::Handle, String>),
+    DownloadMp3(String), // video_id
+    DownloadProgress(String, DownloadProgress), // video_id, progress
+    DownloadStage(String, DownloadStage), // video_id, post-processing stage
+    DownloadLog(String, String), // video_id, log line
+    DownloadCompleted(String, Result<PathBuf, DownloadError>), // video_id, saved file path
+    PlayFile(String), // video_id
+    RunSuggestedAction(String, SuggestedAction), // video_id, follow-up for a failed download
+    OpenUrl(String), // url to open in browser
+    ToggleSettings, // Open/close settings view
+    PickDirectory, // Open native directory picker
+    DirectoryPicked(Option<PathBuf>), // Result from directory picker
+    ShowLogs(String), // video_id
+    CopyLogs(String), // video_id
+    CloseLogs,
+    ShowPlayerLogs,
+    CopyPlayerLogs,
+    ClosePlayerLogs,
+    KeyboardEvent(keyboard::Event), // Keyboard events
+    InstallYtDlp, // Install or update the yt-dlp binary from the selected channel
+    YtDlpInstalled(Result<String, String>), // Installed version or error
+    YtDlpVersionLoaded(Option<String>), // Output of yt-dlp --version
+    YtDlpChannelSelected(UpdateChannel),
+    CheckYtDlpUpdate,
+    YtDlpUpdateChecked(Result<String, String>), // Latest version on the channel
+    RollbackYtDlp,
+    FfmpegVersionsLoaded(FfmpegVersions),
+    PickFfmpeg, // Choose a custom ffmpeg binary
+    FfmpegPicked(Option<PathBuf>),
+    ClearFfmpegPath, // Go back to automatic detection
+    InstallFfmpeg, // Install a static ffmpeg build
+    FfmpegInstalled(Result<(), String>),
+    ShowDiagnostics, // Open the diagnostics view and run the checks
+    CloseDiagnostics,
+    RunDiagnostics,
+    DiagnosticsCompleted(Vec<diagnostics::Check>),
+    CopyDiagnosticsReport,
+    SaveDiagnosticsReport,
+    DiagnosticsReportPathPicked(Option<PathBuf>),
+    CookieBrowserSelected(String),
+    CookieProfileChanged(String),
+    SettingsInputSubmitted, // Enter in one of the Settings text fields
+    PickCookiesFile, // Choose a cookies.txt file
+    CookiesFilePicked(Option<PathBuf>),
+    ClearCookiesFile,
+    RetryClientChanged(usize, String), // index into retry_strategies, player client
+    RetryFormatChanged(usize, String), // index into retry_strategies, format selector
+    AddRetryStrategy,
+    RemoveRetryStrategy(usize),
+    RetryDelayChanged(String),
+    ShowRenameModal(String), // video_id
+    RenameFilenameChanged(String),
+    RenameSectionStartChanged(String),
+    RenameSectionEndChanged(String),
+    RenameSplitChaptersToggled(bool),
+    RenameLyricsLrcToggled(bool),
+    RenameLyricsEmbedToggled(bool),
+    ConfirmDownload,
+    CancelRename,
+    ShareFile(String), // video_id
+    CloseShare,
+    ToggleChapters(String), // video_id
+    ChaptersLoaded(String, Result<Vec<chapters::Chapter>, String>), // video_id, chapters
+    ChaptersSplit(String, Result<Vec<PathBuf>, String>), // video_id, chapter files
+    LoudnessProcessed(String, Result<loudness::Loudness, String>), // video_id, measurement
+    LyricsProcessed(String, Result<String, String>), // video_id, summary
+    ShowTrimEditor(String), // video_id
+    WaveformLoaded(Result<trim::Waveform, String>),
+    TrimStartChanged(f64),
+    TrimEndChanged(f64),
+    TrimFadeInChanged(String),
+    TrimFadeOutChanged(String),
+    ApplyTrim,
+    TrimApplied(Result<(), String>),
+    CloseTrimEditor,
+    PollPhoneRequests, // Check the share server for requests sent from the phone
+    ApprovePhoneRequest(usize), // index into phone_requests
+    DismissPhoneRequest(usize), // index into phone_requests
+    PhoneRequestResolved(Result<Vec<VideoResult>, String>),
+    SearchPlaylistLink(bool), // search the whole playlist, or just the video, of playlist_choice
+    DismissPlaylistChoice,
+    ClipboardWatchToggled(bool),
+    PollClipboard, // Look for a newly copied YouTube link
+    SearchClipboardLink,
+    DownloadClipboardLink,
+    DismissClipboardLink,
+    ClipboardLinkResolved(Result<Vec<VideoResult>, String>),
+    ApiToggled(bool),
+    ApiPortChanged(String),
+    CopyApiToken,
+    RegenerateApiToken,
+    ApiRequest(ApiRequest),
+    ApiSearchCompleted(ApiReply, Result<Vec<VideoResult>, String>),
+    ApiEnqueueResolved(ApiReply, Option<String>, bool, Result<Vec<VideoResult>, String>), // filename, queue every entry
+    ToggleQrMode, // Switch between the download URL and Wi-Fi join QR codes
+    WifiSsidChanged(String),
+    WifiPasswordChanged(String),
+    SaveQrCode,
+    QrSavePathPicked(Option<PathBuf>),
+    CopyQrCode,
+    ToggleShortcuts, // Show or hide the keyboard shortcuts overlay
+    ToggleSubscriptions,
+    SubscriptionUrlChanged(String),
+    AddSubscription,
+    RemoveSubscription(String), // subscription url
+    SubscriptionAutoToggled(String, bool), // subscription url, queue new items without asking
+    SubscriptionFormatSelected(String, AudioFormat), // subscription url, format
+    PickSubscriptionFolder(String), // subscription url
+    SubscriptionFolderPicked(String, Option<PathBuf>), // subscription url, folder
+    ClearSubscriptionFolder(String), // subscription url
+    SubscriptionIntervalChanged(String),
+    SubscriptionIntervalSubmitted,
+    CheckSubscriptions, // Re-list the subscriptions that are due
+    CheckSubscriptionNow(String), // subscription url
+    SubscriptionListed(String, Result<Vec<VideoResult>, String>), // subscription url, entries
+    ApproveSubscriptionItem(String, String), // subscription url, video_id
+    DismissSubscriptionItem(String, String), // subscription url, video_id
+    ApproveAllSubscriptionItems(String), // subscription url
+    DismissAllSubscriptionItems(String), // subscription url
+    FilterTextChanged(String),
+    FilterExcludeChanged(String),
+    FilterMinViewsChanged(String),
+    FilterMinDurationChanged(String),
+    FilterMaxDurationChanged(String),
+    SortSelected(ResultSort),
+    ClearFilters,
+    ResultChecked(String, bool), // video_id, ticked; shift extends from the last ticked result
+    ClearChecked,
+    DownloadChecked,
+    QueueCheckedWithTemplate,
+    BulkTemplateChanged(String),
+    BulkTemplateSubmitted,
+    ShareChecked, // Share every downloaded selected result from one Wi-Fi page
+    CopyCheckedUrls,
+    KeyBindingChanged(KeyAction, String),
+    InitTray,
+    PollTray, // Check the tray icon for menu clicks
+    WindowCloseRequested(window::Id),
+    NotificationsToggled(bool),
+    SponsorBlockToggled(bool),
+    SponsorBlockUrlChanged(String),
+    SponsorBlockCategoryToggled(&'static str, bool),
+    LoudnessModeSelected(LoudnessMode),
+    LoudnessTargetChanged(String),
+    LoudnessTargetSubmitted,
+    MinimizeToTrayToggled(bool),
 }
 
 struct Songbird {
@@ -738,12 +1561,21 @@ struct Songbird {
     search_results: Vec<VideoResult>,
     is_searching: bool,
     error_message: Option<String>,
-    thumbnails: HashMap<String, image::Handle>,
+    thumbnails: HashMap<String, image::Handle>, // only for results the filter shows
+    thumbnails_loading: HashSet<String>, // video ids with a thumbnail load in flight
     downloading: HashMap<String, bool>, // video_id -> is_downloading
     download_messages: HashMap<String, String>, // video_id -> status message
-    download_progress: HashMap<String, f32>, // video_id -> progress (0-100)
+    download_progress: HashMap<String, DownloadProgress>, // video_id -> progress
     download_logs: HashMap<String, Vec<String>>, // video_id -> log lines
     downloaded_files: HashMap<String, PathBuf>, // video_id -> file path
+    download_filenames: HashMap<String, String>, // video_id -> filename chosen for the download
+    download_requests: HashMap<String, QueuedDownload>, // video_id -> the download as queued, for retries
+    split_chapters: HashSet<String>, // downloads to cut into chapter files when they finish
+    lyrics_requests: HashMap<String, LyricsOptions>, // downloads to fetch lyrics for when they finish
+    video_chapters: HashMap<String, Vec<chapters::Chapter>>, // video_id -> chapters, once fetched
+    chapters_status: HashMap<String, String>, // video_id -> loading, error or "no chapters" message
+    expanded_chapters: HashSet<String>, // results showing their chapter list
+    download_errors: HashMap<String, DownloadError>, // video_id -> why the last attempt failed
     config: Config,
     show_settings: bool,
     show_logs_for: Option<String>, // video_id to show logs for
@@ -751,21 +1583,151 @@ struct Songbird {
     results_scroll_id: ScrollableId,
     ytdlp_status: String, // Status message for yt-dlp installation/update
     ytdlp_installing: bool,
+    ytdlp_installed: bool, // the managed binary exists and is executable, checked when it changes
+    ytdlp_can_roll_back: bool, // a binary replaced by the last update is kept
+    ytdlp_version: Option<String>, // Installed version, if yt-dlp runs
+    ytdlp_latest: Option<String>, // Latest version on the selected channel, once checked
+    ytdlp_checking: bool,
+    ffmpeg_status: String, // Status message for ffmpeg installation
+    ffmpeg_installing: bool,
+    ffmpeg_versions: FfmpegVersions,
+    ffmpeg_location: Option<String>, // looked up when the path changes, not on every render
+    show_diagnostics: bool,
+    diagnostics: Vec<diagnostics::Check>,
+    diagnostics_running: bool,
+    diagnostics_status: Option<String>, // result of the last copy/save of the report
     player_logs: Vec<String>,
     show_player_logs: bool,
     rename_modal: Option<RenameModal>,
+    trim_editor: Option<TrimEditor>,
     share_modal: Option<ShareModal>,
+    download_queue: VecDeque<QueuedDownload>, // downloads waiting for a free slot
+    phone_requests: Vec<String>, // links/searches sent from the phone awaiting approval
+    tray: Option<Tray>,
+    tray_error: Option<String>, // why the tray icon couldn't be created
+    api_server: Option<ApiServer>,
+    api_error: Option<String>, // why the API couldn't be started
+    clipboard: Option<arboard::Clipboard>, // created on first use and kept, see clipboard()
+    clipboard_seen: Option<String>, // last clipboard text looked at by the watcher, or copied by us
+    clipboard_link: Option<String>, // copied YouTube link offered for search or download
+    playlist_choice: Option<YouTubeLink>, // searched link to a video in a playlist, awaiting "this video or the playlist"
+    link_starts: HashMap<String, u64>, // video_id -> seconds the searched link started playing at
+    logs_copy_status: Option<String>, // result of the last copy from a log viewer
+    search_history: SearchHistory,
+    download_history: DownloadHistory,
+    history_open: bool, // show the history dropdown under the search box
+    history_selection: Option<usize>, // highlighted suggestion, moved with up/down
+    searched_query: Option<String>, // query the current results came from
+    selected_video: Option<String>, // result highlighted for keyboard actions
+    show_shortcuts: bool,
+    show_subscriptions: bool,
+    subscription_url_input: String,
+    loudness_target_input: String, // as typed, so "14." keeps its dot until the number is finished
+    subscription_error: Option<String>, // why the last URL couldn't be added
+    subscription_new: HashMap<String, Vec<VideoResult>>, // subscription url -> new items awaiting approval
+    subscription_status: HashMap<String, String>, // subscription url -> result of the last check
+    subscriptions_checking: HashSet<String>, // subscription urls being listed right now
+    subscription_seen: SeenItems,
+    subscription_interval_input: String, // as typed, applied once it's a whole number of minutes
+    cookie_profile_input: String, // as typed, saved on Enter or when Settings closes
+    retry_strategy_inputs: Vec<RetryStrategy>, // retry attempts as edited, saved like the cookie profile
+    retry_delay_input: String,
+    key_binding_inputs: KeyBindings, // shortcuts as typed, saved like the cookie profile
+    sponsorblock_url_input: String, // saved like the cookie profile
+    bulk_template_input: String, // saved on Enter or once queued with
+    subscription_downloads: Vec<(String, String)>, // video_id, title of recent downloads queued from subscriptions
+    checked_results: HashSet<String>, // video_ids ticked for bulk actions
+    check_anchor: Option<String>, // last ticked result, the start of a shift-click range
+    keyboard_modifiers: keyboard::Modifiers,
+    bulk_status: Option<String>, // result of the last bulk action
+    result_filter: ResultFilter,
+}
+
+#[derive(Debug, Clone)]
+struct QueuedDownload {
+    video_id: String,
+    filename: String,
+    section: Option<String>, // yt-dlp --download-sections range, e.g. "*30-95.5"
+    split_chapters: bool, // cut into one file per chapter once downloaded
+    lyrics: LyricsOptions,
+    output_dir: Option<PathBuf>, // instead of the download directory from Settings
+    format: AudioFormat,
+}
+
+impl QueuedDownload {
+    fn new(video_id: String, filename: String) -> Self {
+        Self {
+            video_id,
+            filename,
+            section: None,
+            split_chapters: false,
+            lyrics: LyricsOptions::default(),
+            output_dir: None,
+            format: AudioFormat::default(),
+        }
+    }
+}
+
+// Steps run on a finished download, in order; each result message moves on to the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum PostStep {
+    Loudness,
+    Lyrics,
+    Chapters,
 }
 
 struct RenameModal {
     video_id: String,
     filename: String,
+    section_start: String, // optional, only download from here
+    section_end: String, // optional, only download up to here
+    section_error: Option<String>,
+    split_chapters: bool,
+    lyrics: LyricsOptions,
+}
+
+struct TrimEditor {
+    file_path: PathBuf,
+    waveform: Option<trim::Waveform>,
+    start: f64, // seconds
+    end: f64, // seconds
+    fade_in: String, // seconds, as typed
+    fade_out: String, // seconds, as typed
+    busy: bool, // loading the waveform or re-encoding
+    status: Option<String>,
 }
 
 struct ShareModal {
     server: Option<ShareServer>,
-    qr_code: String,
+    qr_image: Option<QrImage>,
+    qr_handle: Option<image::Handle>,
+    qr_mode: QrMode,
+    qr_status: Option<String>, // result of the last save/copy
     url: String,
+    file_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum QrMode {
+    Url,
+    WifiJoin,
+}
+
+impl ShareModal {
+    // Re-render the QR code for the current mode
+    fn refresh_qr(&mut self, config: &Config) {
+        let payload = match self.qr_mode {
+            QrMode::Url => Some(self.url.clone()),
+            QrMode::WifiJoin => config.wifi_ssid.as_deref()
+                .filter(|ssid| !ssid.is_empty())
+                .map(|ssid| wifi_share::wifi_join_payload(ssid, config.wifi_password.as_deref().unwrap_or(""))),
+        };
+        
+        self.qr_image = payload.and_then(|data| wifi_share::render_qr_code(&data).ok());
+        self.qr_handle = self.qr_image.as_ref()
+            .map(|qr| image::Handle::from_rgba(qr.width, qr.height, qr.pixels.clone()));
+        self.qr_status = None;
+    }
 }
 
 impl Songbird {
@@ -776,35 +1738,131 @@ impl Songbird {
             "yt-dlp not found - click Install to download".to_string()
         };
         
+        let mut config = Config::load();
+        let mut subscription_seen = SeenItems::load();
+        if subscription_seen.take_legacy(&mut config.channel_subscriptions) {
+            if let Err(e) = subscription_seen.save().and_then(|()| config.save()) {
+                eprintln!("Failed to move seen subscription items out of the config: {}", e);
+            }
+        }
+        let subscription_interval_input = config.subscription_check_mins.to_string();
+        let cookie_profile_input = config.browser_profile.clone().unwrap_or_default();
+        let retry_strategy_inputs = config.retry_strategies.clone();
+        let retry_delay_input = config.retry_base_delay_secs.to_string();
+        let key_binding_inputs = config.key_bindings.clone();
+        let sponsorblock_url_input = config.sponsorblock_api_url.clone();
+        let bulk_template_input = config.bulk_filename_template.clone();
+        let ffmpeg_location = find_ffmpeg(config.ffmpeg_path.as_deref());
+        let loudness_target_input = format!("{}", -config.loudness_target_lufs);
         let search_input_id = TextInputId::unique();
         let results_scroll_id = ScrollableId::unique();
         let focus_task = text_input::focus(search_input_id.clone());
         
-        let app = Self {
+        let mut app = Self {
             search_query: String::new(),
             search_results: Vec::new(),
             is_searching: false,
             error_message: None,
             thumbnails: HashMap::new(),
+            thumbnails_loading: HashSet::new(),
             downloading: HashMap::new(),
             download_messages: HashMap::new(),
             download_progress: HashMap::new(),
             download_logs: HashMap::new(),
             downloaded_files: HashMap::new(),
-            config: Config::load(),
+            download_filenames: HashMap::new(),
+            download_requests: HashMap::new(),
+            split_chapters: HashSet::new(),
+            lyrics_requests: HashMap::new(),
+            video_chapters: HashMap::new(),
+            chapters_status: HashMap::new(),
+            expanded_chapters: HashSet::new(),
+            download_errors: HashMap::new(),
+            config,
             show_settings: false,
             show_logs_for: None,
             search_input_id,
             results_scroll_id,
             ytdlp_status,
+            ytdlp_installed: is_ytdlp_installed(),
+            ytdlp_can_roll_back: ytdlp_update::previous_binary_path(&get_ytdlp_path()).exists(),
             ytdlp_installing: false,
+            ytdlp_version: None,
+            ytdlp_latest: None,
+            ytdlp_checking: false,
+            ffmpeg_status: String::new(),
+            ffmpeg_installing: false,
+            ffmpeg_versions: FfmpegVersions::default(),
+            ffmpeg_location,
+            show_diagnostics: false,
+            diagnostics: Vec::new(),
+            diagnostics_running: false,
+            diagnostics_status: None,
             player_logs: Vec::new(),
             show_player_logs: false,
             rename_modal: None,
+            trim_editor: None,
             share_modal: None,
+            download_queue: VecDeque::new(),
+            phone_requests: Vec::new(),
+            tray: None,
+            tray_error: None,
+            api_server: None,
+            api_error: None,
+            clipboard: None,
+            clipboard_seen: None,
+            clipboard_link: None,
+            playlist_choice: None,
+            link_starts: HashMap::new(),
+            logs_copy_status: None,
+            search_history: SearchHistory::load(),
+            download_history: DownloadHistory::load(),
+            history_open: false,
+            history_selection: None,
+            searched_query: None,
+            selected_video: None,
+            show_shortcuts: false,
+            show_subscriptions: false,
+            subscription_url_input: String::new(),
+            loudness_target_input,
+            subscription_error: None,
+            subscription_new: HashMap::new(),
+            subscription_status: HashMap::new(),
+            subscriptions_checking: HashSet::new(),
+            subscription_seen,
+            subscription_interval_input,
+            cookie_profile_input,
+            retry_strategy_inputs,
+            retry_delay_input,
+            key_binding_inputs,
+            sponsorblock_url_input,
+            bulk_template_input,
+            subscription_downloads: Vec::new(),
+            checked_results: HashSet::new(),
+            check_anchor: None,
+            keyboard_modifiers: keyboard::Modifiers::default(),
+            bulk_status: None,
+            result_filter: ResultFilter::default(),
         };
         
-        (app, focus_task)
+        let version_task = Task::perform(
+            ytdlp_update::installed_version(get_ytdlp_path()),
+            Message::YtDlpVersionLoaded,
+        );
+        
+        let ffmpeg_task = Task::perform(
+            load_ffmpeg_versions(app.ffmpeg_location.clone()),
+            Message::FfmpegVersionsLoaded,
+        );
+        
+        // The tray is created once the event loop is running, as macOS requires
+        let tray_task = Task::done(Message::InitTray);
+        
+        if app.config.api_enabled {
+            app.start_api_server();
+        }
+        
+        (app, Task::batch([focus_task, version_task, ffmpeg_task, tray_task]))
     }
 }
 
@@ -814,63 +1872,110 @@ impl Songbird {
             Message::SearchInputChanged(value) => {
                 self.search_query = value;
                 self.error_message = None;
+                self.playlist_choice = None;
+                self.history_open = true;
+                self.history_selection = None;
                 Task::none()
             }
             Message::SearchPressed => {
+                // Enter on a highlighted suggestion searches for it instead
+                if self.history_open {
+                    if let Some(entry) = self.history_selection
+                        .and_then(|index| self.search_history.suggestions(&self.search_query).get(index).copied())
+                    {
+                        self.search_query = entry.query.clone();
+                    }
+                }
+                self.history_open = false;
+                self.history_selection = None;
+                
                 if self.search_query.trim().is_empty() {
                     self.error_message = Some("Please enter a search query".to_string());
                     return Task::none();
                 }
+                
+                // A link to a video in a playlist could mean either; ask which
+                let link = YouTubeLink::parse(&self.search_query);
+                if matches!(link, YouTubeLink::VideoInPlaylist { .. }) {
+                    self.playlist_choice = Some(link);
+                    return Task::none();
+                }
+                self.playlist_choice = None;
+                if let Some((video_id, start)) = link.start() {
+                    self.link_starts.insert(video_id.to_string(), start);
+                }
 
                 self.is_searching = true;
                 self.error_message = None;
                 let query = self.search_query.clone();
+                let cookie_args = self.config.cookie_args();
+                
+                self.search_history.record(&query);
+                if let Err(e) = self.search_history.save() {
+                    self.error_message = Some(format!("Failed to save search history: {}", e));
+                }
+                self.searched_query = Some(query.clone());
 
                 Task::perform(
                     async move {
-                        search_youtube(&query).await
+                        search_youtube(&query, &cookie_args).await
                     },
                     Message::SearchCompleted,
                 )
             }
+            Message::HistorySelected(query) => {
+                self.search_query = query;
+                self.history_open = false;
+                self.history_selection = None;
+                self.update(Message::SearchPressed)
+            }
+            Message::ToggleHistoryPin(query) => {
+                self.search_history.toggle_pin(&query);
+                if let Err(e) = self.search_history.save() {
+                    self.error_message = Some(format!("Failed to save search history: {}", e));
+                }
+                Task::none()
+            }
+            Message::RemoveHistoryEntry(query) => {
+                self.search_history.remove(&query);
+                if let Err(e) = self.search_history.save() {
+                    self.error_message = Some(format!("Failed to save search history: {}", e));
+                }
+                self.history_selection = None;
+                Task::none()
+            }
             Message::SearchCompleted(result) => {
                 self.is_searching = false;
                 match result {
                     Ok(results) => {
                         self.search_results = results;
+                        self.selected_video = None;
+                        self.checked_results.clear();
+                        self.check_anchor = None;
+                        self.bulk_status = None;
                         if self.search_results.is_empty() {
                             self.error_message = Some("No results found".to_string());
                             return Task::none();
                         }
                         
-                        // Load thumbnails for all results
-                        let thumbnail_tasks: Vec<_> = self.search_results
-                            .iter()
-                            .map(|video| {
-                                let url = video.thumbnail.clone();
-                                let video_id = video.video_id.clone();
-                                Task::perform(
-                                    async move {
-                                        match load_thumbnail(&url).await {
-                                            Ok(handle) => (video_id, Ok(handle)),
-                                            Err(e) => (video_id, Err(e)),
-                                        }
-                                    },
-                                    |(video_id, result)| Message::ThumbnailLoaded(video_id, result),
-                                )
-                            })
-                            .collect();
-                        
-                        return Task::batch(thumbnail_tasks);
+                        return self.sync_thumbnails();
                     }
                     Err(e) => {
-                        self.error_message = Some(e);
+                        let age_restricted = DownloadError::classify(e.lines(), None) == DownloadError::AgeRestricted;
+                        self.error_message = Some(if age_restricted {
+                            format!("{}\n{}", e, AGE_RESTRICTED_HINT)
+                        } else {
+                            e
+                        });
                     }
                 }
                 Task::none()
             }
             Message::ThumbnailLoaded(video_id, result) => {
-                if let Ok(handle) = result {
+                self.thumbnails_loading.remove(&video_id);
+                // Ignore thumbnails whose result was replaced or filtered out while loading
+                let on_screen = self.visible_results().iter().any(|video| video.video_id == video_id);
+                if let (Ok(handle), true) = (result, on_screen) {
                     self.thumbnails.insert(video_id, handle);
                 }
                 Task::none()
@@ -892,6 +1997,14 @@ impl Songbird {
                     self.rename_modal = Some(RenameModal {
                         video_id: video_id.clone(),
                         filename,
+                        section_start: String::new(),
+                        section_end: String::new(),
+                        section_error: None,
+                        split_chapters: false,
+                        lyrics: LyricsOptions {
+                            save_lrc: self.config.lyrics_save_lrc,
+                            embed: self.config.lyrics_embed,
+                        },
                     });
                 }
                 Task::none()
@@ -902,44 +2015,103 @@ impl Songbird {
                 }
                 Task::none()
             }
+            Message::RenameSectionStartChanged(value) => {
+                if let Some(modal) = &mut self.rename_modal {
+                    modal.section_start = value;
+                    modal.section_error = None;
+                }
+                Task::none()
+            }
+            Message::RenameSectionEndChanged(value) => {
+                if let Some(modal) = &mut self.rename_modal {
+                    modal.section_end = value;
+                    modal.section_error = None;
+                }
+                Task::none()
+            }
+            Message::RenameSplitChaptersToggled(enabled) => {
+                if let Some(modal) = &mut self.rename_modal {
+                    modal.split_chapters = enabled;
+                    modal.section_error = None;
+                }
+                Task::none()
+            }
+            Message::RenameLyricsLrcToggled(enabled) => {
+                if let Some(modal) = &mut self.rename_modal {
+                    modal.lyrics.save_lrc = enabled;
+                }
+                Task::none()
+            }
+            Message::RenameLyricsEmbedToggled(enabled) => {
+                if let Some(modal) = &mut self.rename_modal {
+                    modal.lyrics.embed = enabled;
+                }
+                Task::none()
+            }
             Message::CancelRename => {
                 self.rename_modal = None;
                 Task::none()
             }
             Message::ConfirmDownload => {
+                // Keep the modal open while the section can't be understood
+                let section = match &mut self.rename_modal {
+                    Some(modal) => match trim::download_section(&modal.section_start, &modal.section_end) {
+                        // Chapter times are for the whole video
+                        Ok(Some(_)) if modal.split_chapters => {
+                            modal.section_error = Some("Chapters can only be split from a full download".to_string());
+                            return Task::none();
+                        }
+                        Ok(section) => section,
+                        Err(e) => {
+                            modal.section_error = Some(e);
+                            return Task::none();
+                        }
+                    },
+                    None => None,
+                };
+                
                 if let Some(modal) = self.rename_modal.take() {
-                    let download_dir = self.config.download_directory.clone().unwrap();
-                    let video_id = modal.video_id.clone();
-                    let filename = modal.filename.clone();
-                    
-                    self.downloading.insert(video_id.clone(), true);
-                    self.download_progress.insert(video_id.clone(), 0.0);
-                    self.download_logs.insert(video_id.clone(), Vec::new());
-                    self.download_messages.insert(video_id.clone(), "Starting download...".to_string());
-                    
-                    let vid_id = video_id.clone();
-                    
-                    // Use Task::run to stream progress updates!
-                    Task::run(
-                        download_mp3_stream_with_filename(video_id, download_dir, filename),
-                        move |update| match update {
-                            DownloadUpdate::Progress(percent) => {
-                                Message::DownloadProgress(vid_id.clone(), percent)
-                            }
-                            DownloadUpdate::Log(log) => {
-                                Message::DownloadLog(vid_id.clone(), log)
-                            }
-                            DownloadUpdate::Completed(result) => {
-                                Message::DownloadCompleted(vid_id.clone(), result)
+                    // Remember which result was chosen for this search
+                    if let Some(query) = &self.searched_query {
+                        if let Some(video) = self.search_results.iter().find(|v| v.video_id == modal.video_id) {
+                            self.search_history.record_download(query, &video.video_id, &video.title);
+                            if let Err(e) = self.search_history.save() {
+                                self.error_message = Some(format!("Failed to save search history: {}", e));
                             }
                         }
-                    )
+                    }
+                    // Offer the same lyrics choice next time
+                    if self.config.lyrics_save_lrc != modal.lyrics.save_lrc || self.config.lyrics_embed != modal.lyrics.embed {
+                        self.config.lyrics_save_lrc = modal.lyrics.save_lrc;
+                        self.config.lyrics_embed = modal.lyrics.embed;
+                        if let Err(e) = self.config.save() {
+                            self.error_message = Some(format!("Failed to save config: {}", e));
+                        }
+                    }
+                    // A download confirmed here starts straight away; the queue only paces
+                    // downloads added in batches from the phone, bulk actions and the like
+                    self.download_queue.retain(|d| d.video_id != modal.video_id);
+                    self.start_download(QueuedDownload {
+                        section,
+                        split_chapters: modal.split_chapters,
+                        lyrics: modal.lyrics,
+                        ..QueuedDownload::new(modal.video_id, modal.filename)
+                    })
                 } else {
                     Task::none()
                 }
             }
             Message::DownloadProgress(video_id, progress) => {
                 self.download_progress.insert(video_id, progress);
+                self.refresh_tray();
+                Task::none()
+            }
+            Message::DownloadStage(video_id, stage) => {
+                // Don't go back from converting to post-processing; only forward
+                let progress = self.download_progress.entry(video_id).or_default();
+                if progress.stage != DownloadStage::Converting || stage != DownloadStage::PostProcessing {
+                    progress.stage = stage;
+                }
                 Task::none()
             }
             Message::DownloadLog(video_id, log) => {
@@ -950,24 +2122,60 @@ impl Songbird {
                 self.downloading.insert(video_id.clone(), false);
                 self.download_progress.remove(&video_id);
                 match result {
-                    Ok(msg) => {
-                        self.download_messages.insert(video_id.clone(), msg);
-                        
-                        // Store the downloaded file path
-                        if let Some(download_dir) = &self.config.download_directory {
-                            // Find the video result to get the filename
-                            if let Some(video) = self.search_results.iter().find(|v| v.video_id == video_id) {
-                                let filename = clean_filename(&video.title);
-                                let file_path = download_dir.join(format!("{}.mp3", filename));
-                                self.downloaded_files.insert(video_id, file_path);
+                    Ok(file_path) => {
+                        // Only offer share/play once the file is really there
+                        if file_path.exists() {
+                            if self.config.notifications_enabled {
+                                notifications::download_finished(&self.download_title(&video_id), &file_path);
+                            }
+                            self.download_messages.insert(video_id.clone(), format!("Downloaded to {}", file_path.display()));
+                            self.download_history.record(&video_id, &self.download_title(&video_id), &file_path);
+                            if let Err(e) = self.download_history.save() {
+                                self.error_message = Some(format!("Failed to save download history: {}", e));
+                            }
+                            self.downloaded_files.insert(video_id.clone(), file_path.clone());
+                            
+                            let post_process = self.post_process(video_id, file_path, PostStep::Loudness);
+                            return Task::batch([post_process, self.start_queued_downloads()]);
+                        } else {
+                            let message = format!("yt-dlp finished but {} was not found", file_path.display());
+                            if self.config.notifications_enabled {
+                                notifications::download_failed(&self.download_title(&video_id), &message);
                             }
+                            self.download_messages.insert(video_id, format!("Error: {}", message));
                         }
                     }
                     Err(e) => {
-                        self.download_messages.insert(video_id, format!("Error: {}", e));
+                        if self.config.notifications_enabled {
+                            notifications::download_failed(&self.download_title(&video_id), &e.message());
+                        }
+                        self.download_messages.insert(video_id.clone(), format!("Error: {}", e));
+                        self.download_errors.insert(video_id, e);
+                    }
+                }
+                self.start_queued_downloads()
+            }
+            Message::RunSuggestedAction(video_id, action) => {
+                match action {
+                    SuggestedAction::UpdateYtDlp => {
+                        self.show_settings = true;
+                        self.update(Message::InstallYtDlp)
+                    }
+                    SuggestedAction::EnableCookies => {
+                        self.show_settings = true;
+                        Task::none()
+                    }
+                    SuggestedAction::InstallFfmpeg => {
+                        self.show_settings = true;
+                        self.update(Message::InstallFfmpeg)
+                    }
+                    SuggestedAction::RetryLater => {
+                        if let Some(download) = self.download_requests.get(&video_id).cloned() {
+                            self.enqueue_download(download);
+                        }
+                        self.start_queued_downloads()
                     }
                 }
-                Task::none()
             }
             Message::OpenUrl(url) => {
                 // Open URL in the default browser
@@ -975,6 +2183,9 @@ impl Songbird {
                 Task::none()
             }
             Message::ToggleSettings => {
+                if self.show_settings {
+                    self.save_settings_inputs();
+                }
                 self.show_settings = !self.show_settings;
                 Task::none()
             }
@@ -998,35 +2209,22 @@ impl Songbird {
                     } else {
                         self.error_message = None;
                     }
+                    return self.start_queued_downloads();
                 }
                 Task::none()
             }
             Message::ShowLogs(video_id) => {
                 self.show_logs_for = Some(video_id);
+                self.logs_copy_status = None;
                 Task::none()
             }
             Message::CopyLogs(video_id) => {
                 if let Some(logs) = self.download_logs.get(&video_id) {
                     let log_text = logs.join("\n");
-                    #[cfg(target_os = "macos")]
-                    {
-                        use std::process::Command;
-                        let mut child = Command::new("pbcopy")
-                            .stdin(std::process::Stdio::piped())
-                            .spawn()
-                            .ok();
-                        if let Some(ref mut child) = child {
-                            use std::io::Write;
-                            if let Some(ref mut stdin) = child.stdin {
-                                let _ = stdin.write_all(log_text.as_bytes());
-                            }
-                        }
-                    }
-                    #[cfg(not(target_os = "macos"))]
-                    {
-                        // For Linux, we'd use xclip or similar, but for now just show message
-                        self.error_message = Some("Logs copied! (On Linux, please manually copy from the log viewer)".to_string());
-                    }
+                    self.logs_copy_status = Some(match self.copy_to_clipboard(log_text) {
+                        Ok(()) => "Logs copied to clipboard".to_string(),
+                        Err(e) => format!("Failed to copy logs: {}", e),
+                    });
                 }
                 Task::none()
             }
@@ -1036,24 +2234,15 @@ impl Songbird {
             }
             Message::ShowPlayerLogs => {
                 self.show_player_logs = true;
+                self.logs_copy_status = None;
                 Task::none()
             }
             Message::CopyPlayerLogs => {
                 let log_text = self.player_logs.join("\n");
-                #[cfg(target_os = "macos")]
-                {
-                    use std::process::Command;
-                    let mut child = Command::new("pbcopy")
-                        .stdin(std::process::Stdio::piped())
-                        .spawn()
-                        .ok();
-                    if let Some(ref mut child) = child {
-                        use std::io::Write;
-                        if let Some(ref mut stdin) = child.stdin {
-                            let _ = stdin.write_all(log_text.as_bytes());
-                        }
-                    }
-                }
+                self.logs_copy_status = Some(match self.copy_to_clipboard(log_text) {
+                    Ok(()) => "Logs copied to clipboard".to_string(),
+                    Err(e) => format!("Failed to copy logs: {}", e),
+                });
                 Task::none()
             }
             Message::ClosePlayerLogs => {
@@ -1061,104 +2250,2550 @@ impl Songbird {
                 Task::none()
             }
             Message::KeyboardEvent(event) => {
-                if let keyboard::Event::KeyPressed {
-                    key: keyboard::Key::Character(c),
-                    modifiers,
-                    ..
-                } = event
-                {
-                    if modifiers.command() && c.as_ref() == "k" {
+                if let keyboard::Event::ModifiersChanged(modifiers) = event {
+                    self.keyboard_modifiers = modifiers;
+                    return Task::none();
+                }
+                
+                let keyboard::Event::KeyPressed { key, modifiers, .. } = event else {
+                    return Task::none();
+                };
+                
+                match key.as_ref() {
+                    keyboard::Key::Character("k") if modifiers.command() => {
                         return text_input::focus(self.search_input_id.clone());
                     }
-                }
-                Task::none()
-            }
-            Message::InstallYtDlp => {
-                self.ytdlp_installing = true;
-                self.ytdlp_status = "Downloading yt-dlp...".to_string();
-                Task::perform(download_ytdlp(), Message::YtDlpInstalled)
-            }
-            Message::YtDlpInstalled(result) => {
-                self.ytdlp_installing = false;
-                match result {
-                    Ok(()) => {
-                        self.ytdlp_status = "yt-dlp installed successfully!".to_string();
+                    keyboard::Key::Named(keyboard::key::Named::Escape) => {
+                        return self.close_top_view();
                     }
-                    Err(e) => {
-                        self.ytdlp_status = format!("Installation failed: {}", e);
+                    keyboard::Key::Named(keyboard::key::Named::ArrowDown) if self.history_open || self.search_results.is_empty() => {
+                        let count = self.search_history.suggestions(&self.search_query).len();
+                        if count > 0 {
+                            self.history_open = true;
+                            self.history_selection = Some(match self.history_selection {
+                                Some(index) => (index + 1).min(count - 1),
+                                None => 0,
+                            });
+                        }
+                        return Task::none();
                     }
+                    keyboard::Key::Named(keyboard::key::Named::ArrowUp) if self.history_open => {
+                        self.history_selection = match self.history_selection {
+                            Some(0) | None => None,
+                            Some(index) => Some(index - 1),
+                        };
+                        return Task::none();
+                    }
+                    _ => {}
                 }
-                Task::none()
-            }
-            Message::ShareFile(video_id) => {
-                // Get the file path for this video
-                if let Some(file_path) = self.downloaded_files.get(&video_id) {
-                    #[cfg(target_os = "macos")]
-                    {
-                        // On macOS, try to use AirDrop
-                        match macos_share::share_file_via_airdrop(file_path) {
-                            Ok(()) => {
-                                // AirDrop picker shown successfully
-                                return Task::none();
-                            }
-                            Err(e) => {
-                                // Fall back to Wi-Fi share on error
-                                eprintln!("AirDrop failed: {}, falling back to Wi-Fi share", e);
-                            }
+                
+                // The remaining shortcuts act on the results list, so only while it's showing
+                if modifiers.command() || modifiers.alt() || self.is_results_covered() {
+                    return Task::none();
+                }
+                
+                let action = match key.as_ref() {
+                    keyboard::Key::Named(keyboard::key::Named::ArrowDown) => Some(KeyAction::Next),
+                    keyboard::Key::Named(keyboard::key::Named::ArrowUp) => Some(KeyAction::Previous),
+                    _ => self.config.key_bindings.action_for(&key),
+                };
+                
+                let Some(action) = action else {
+                    return Task::none();
+                };
+                
+                if action == KeyAction::ShowShortcuts {
+                    return self.update(Message::ToggleShortcuts);
+                }
+                if self.show_shortcuts {
+                    return Task::none();
+                }
+                
+                match action {
+                    KeyAction::Next => self.move_selection(1),
+                    KeyAction::Previous => self.move_selection(-1),
+                    KeyAction::Download => {
+                        let Some(video_id) = self.selected_video.clone() else {
+                            return Task::none();
+                        };
+                        let busy = self.downloading.get(&video_id).copied().unwrap_or(false)
+                            || self.download_queue.iter().any(|d| d.video_id == video_id);
+                        if busy {
+                            Task::none()
+                        } else {
+                            self.update(Message::DownloadMp3(video_id))
                         }
                     }
-                    
-                    // On Linux or if macOS AirDrop fails, use Wi-Fi share
-                    match ShareServer::new(file_path) {
-                        Ok(server) => {
-                            let qr_code = server.generate_qr_code().unwrap_or_else(|_| "QR code generation failed".to_string());
-                            let url = server.get_url().unwrap_or_else(|_| "URL unavailable".to_string());
-                            
-                            if let Err(e) = server.start() {
-                                eprintln!("Failed to start share server: {}", e);
-                                return Task::none();
-                            }
-                            
-                            self.share_modal = Some(ShareModal {
-                                server: Some(server),
-                                qr_code,
-                                url,
-                            });
+                    KeyAction::OpenInBrowser => {
+                        match self.selected_result() {
+                            Some(video) => self.update(Message::OpenUrl(video.url())),
+                            None => Task::none(),
                         }
-                        Err(e) => {
-                            eprintln!("Failed to create share server: {}", e);
+                    }
+                    KeyAction::ShowLogs => {
+                        match self.selected_video.clone().filter(|id| self.download_logs.contains_key(id)) {
+                            Some(video_id) => self.update(Message::ShowLogs(video_id)),
+                            None => Task::none(),
+                        }
+                    }
+                    KeyAction::Share => {
+                        match self.selected_video.clone().filter(|id| self.downloaded_files.contains_key(id)) {
+                            Some(video_id) => self.update(Message::ShareFile(video_id)),
+                            None => Task::none(),
                         }
                     }
+                    KeyAction::ShowShortcuts => Task::none(),
                 }
-                Task::none()
             }
-            Message::CloseShare => {
-                if let Some(modal) = self.share_modal.take() {
-                    if let Some(server) = modal.server {
-                        server.stop();
+            Message::ResultChecked(video_id, checked) => {
+                // Ranges follow the list as shown, after filtering and sorting
+                let visible: Vec<String> = self.visible_results()
+                    .iter()
+                    .map(|video| video.video_id.clone())
+                    .collect();
+                let anchor = self.check_anchor.as_ref()
+                    .and_then(|anchor| visible.iter().position(|id| id == anchor));
+                let target = visible.iter().position(|id| *id == video_id);
+                
+                let range = match (anchor, target) {
+                    (Some(anchor), Some(target)) if self.keyboard_modifiers.shift() => {
+                        anchor.min(target)..=anchor.max(target)
+                    }
+                    _ => {
+                        let index = target.unwrap_or(0);
+                        index..=index
+                    }
+                };
+                
+                if target.is_some() {
+                    for id in &visible[range] {
+                        if checked {
+                            self.checked_results.insert(id.clone());
+                        } else {
+                            self.checked_results.remove(id);
+                        }
                     }
                 }
+                
+                self.check_anchor = Some(video_id);
+                self.bulk_status = None;
                 Task::none()
             }
-
-        }
-    }
-    
-    fn subscription(&self) -> Subscription<Message> {
-        event::listen().map(|event| {
-            if let event::Event::Keyboard(keyboard_event) = event {
-                Message::KeyboardEvent(keyboard_event)
-            } else {
-                Message::KeyboardEvent(keyboard::Event::KeyReleased {
-                    key: keyboard::Key::Character("".into()),
-                    modifiers: keyboard::Modifiers::default(),
-                    location: keyboard::Location::Standard,
+            Message::ClearChecked => {
+                self.checked_results.clear();
+                self.check_anchor = None;
+                self.bulk_status = None;
+                Task::none()
+            }
+            Message::DownloadChecked | Message::QueueCheckedWithTemplate => {
+                if self.config.download_directory.is_none() {
+                    self.show_settings = true;
+                    self.error_message = Some("Please select a download directory in settings".to_string());
+                    return Task::none();
+                }
+                
+                let use_template = matches!(message, Message::QueueCheckedWithTemplate);
+                if use_template {
+                    self.save_bulk_template();
+                }
+                let downloads: Vec<(String, String)> = self.checked_videos()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, video)| {
+                        let filename = if use_template {
+                            render_filename_template(&self.config.bulk_filename_template, video, index + 1)
+                        } else {
+                            clean_filename(&video.title)
+                        };
+                        (video.video_id.clone(), filename)
+                    })
+                    .collect();
+                
+                let count = downloads.len();
+                for (video_id, filename) in downloads {
+                    // Results hidden by the filter weren't queued, so they stay ticked
+                    self.checked_results.remove(&video_id);
+                    self.enqueue_download(QueuedDownload::new(video_id, filename));
+                }
+                
+                self.bulk_status = Some(format!("Queued {} download(s)", count));
+                self.check_anchor = None;
+                self.start_queued_downloads()
+            }
+            Message::BulkTemplateChanged(template) => {
+                self.bulk_template_input = template;
+                Task::none()
+            }
+            Message::BulkTemplateSubmitted => {
+                self.save_bulk_template();
+                Task::none()
+            }
+            Message::ShareChecked => {
+                let video_ids: Vec<String> = self.checked_videos()
+                    .into_iter()
+                    .map(|video| video.video_id.clone())
+                    .collect();
+                
+                let mut files = Vec::new();
+                for video_id in &video_ids {
+                    if self.verify_downloaded_file(video_id) {
+                        files.extend(self.downloaded_files.get(video_id).cloned());
+                    }
+                }
+                
+                if files.is_empty() {
+                    self.bulk_status = Some("None of the selected results have been downloaded yet".to_string());
+                } else {
+                    if files.len() < video_ids.len() {
+                        self.bulk_status = Some(format!(
+                            "Sharing {} of {} selected; the rest haven't been downloaded",
+                            files.len(),
+                            video_ids.len()
+                        ));
+                    }
+                    self.open_share_modal(&files);
+                }
+                Task::none()
+            }
+            Message::CopyCheckedUrls => {
+                let urls = self.checked_videos()
+                    .into_iter()
+                    .map(|video| video.url())
+                    .collect::<Vec<_>>();
+                let count = urls.len();
+                
+                let result = self.copy_to_clipboard(urls.join("\n"));
+                
+                self.bulk_status = Some(match result {
+                    Ok(()) => format!("Copied {} URL(s)", count),
+                    Err(e) => format!("Failed to copy URLs: {}", e),
+                });
+                Task::none()
+            }
+            Message::FilterTextChanged(value) => {
+                self.result_filter.text = value;
+                self.sync_thumbnails()
+            }
+            Message::FilterExcludeChanged(value) => {
+                self.result_filter.exclude = value;
+                self.sync_thumbnails()
+            }
+            Message::FilterMinViewsChanged(value) => {
+                self.result_filter.min_views = value;
+                self.sync_thumbnails()
+            }
+            Message::FilterMinDurationChanged(value) => {
+                self.result_filter.min_duration = value;
+                self.sync_thumbnails()
+            }
+            Message::FilterMaxDurationChanged(value) => {
+                self.result_filter.max_duration = value;
+                self.sync_thumbnails()
+            }
+            Message::SortSelected(sort) => {
+                self.result_filter.sort = sort;
+                Task::none()
+            }
+            Message::ClearFilters => {
+                self.result_filter = ResultFilter {
+                    sort: self.result_filter.sort,
+                    ..ResultFilter::default()
+                };
+                self.sync_thumbnails()
+            }
+            Message::ToggleShortcuts => {
+                self.show_shortcuts = !self.show_shortcuts;
+                Task::none()
+            }
+            Message::ToggleSubscriptions => {
+                self.show_subscriptions = !self.show_subscriptions;
+                Task::none()
+            }
+            Message::SubscriptionUrlChanged(url) => {
+                self.subscription_url_input = url;
+                self.subscription_error = None;
+                Task::none()
+            }
+            Message::AddSubscription => {
+                let subscription = match ChannelSubscription::new(&self.subscription_url_input) {
+                    Ok(subscription) => subscription,
+                    Err(e) => {
+                        self.subscription_error = Some(e);
+                        return Task::none();
+                    }
+                };
+                if self.config.channel_subscriptions.iter().any(|s| s.url == subscription.url) {
+                    self.subscription_error = Some("Already subscribed".to_string());
+                    return Task::none();
+                }
+                
+                let url = subscription.url.clone();
+                self.config.channel_subscriptions.push(subscription);
+                self.subscription_url_input.clear();
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(format!("Failed to save config: {}", e));
+                }
+                self.check_subscription(&url)
+            }
+            Message::RemoveSubscription(url) => {
+                self.config.channel_subscriptions.retain(|s| s.url != url);
+                self.subscription_new.remove(&url);
+                self.subscription_status.remove(&url);
+                self.subscription_seen.remove(&url);
+                self.save_subscription_state();
+                Task::none()
+            }
+            Message::SubscriptionAutoToggled(url, enabled) => {
+                if let Some(subscription) = self.config.channel_subscriptions.iter_mut().find(|s| s.url == url) {
+                    subscription.auto_download = enabled;
+                    if let Err(e) = self.config.save() {
+                        self.error_message = Some(format!("Failed to save config: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            Message::SubscriptionFormatSelected(url, format) => {
+                if let Some(subscription) = self.config.channel_subscriptions.iter_mut().find(|s| s.url == url) {
+                    subscription.format = format;
+                    if let Err(e) = self.config.save() {
+                        self.error_message = Some(format!("Failed to save config: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            Message::PickSubscriptionFolder(url) => {
+                Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Select Download Folder")
+                            .pick_folder()
+                            .await
+                            .map(|folder| folder.path().to_path_buf())
+                    },
+                    move |folder| Message::SubscriptionFolderPicked(url.clone(), folder),
+                )
+            }
+            Message::SubscriptionFolderPicked(url, folder) => {
+                if let Some(folder) = folder {
+                    if let Some(subscription) = self.config.channel_subscriptions.iter_mut().find(|s| s.url == url) {
+                        subscription.output_dir = Some(folder);
+                        if let Err(e) = self.config.save() {
+                            self.error_message = Some(format!("Failed to save config: {}", e));
+                        }
+                    }
+                }
+                Task::none()
+            }
+            Message::ClearSubscriptionFolder(url) => {
+                if let Some(subscription) = self.config.channel_subscriptions.iter_mut().find(|s| s.url == url) {
+                    subscription.output_dir = None;
+                    if let Err(e) = self.config.save() {
+                        self.error_message = Some(format!("Failed to save config: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            Message::SubscriptionIntervalChanged(value) => {
+                // Keep what's typed and only take it once it's a usable interval
+                if let Some(mins) = value.trim().parse::<u64>().ok().filter(|mins| *mins >= channel_subscriptions::MIN_CHECK_MINS) {
+                    self.config.subscription_check_mins = mins;
+                    if let Err(e) = self.config.save() {
+                        self.error_message = Some(format!("Failed to save config: {}", e));
+                    }
+                }
+                self.subscription_interval_input = value;
+                Task::none()
+            }
+            Message::SubscriptionIntervalSubmitted => {
+                if self.subscription_interval_input.trim().parse::<u64>().ok() != Some(self.config.subscription_check_mins) {
+                    self.error_message = Some(format!(
+                        "Subscriptions can be checked at most every {} minutes",
+                        channel_subscriptions::MIN_CHECK_MINS
+                    ));
+                }
+                self.subscription_interval_input = self.config.subscription_check_mins.to_string();
+                Task::none()
+            }
+            Message::CheckSubscriptions => {
+                let due: Vec<String> = self.config.channel_subscriptions.iter()
+                    .filter(|s| s.is_due(self.config.subscription_check_mins))
+                    .map(|s| s.url.clone())
+                    .collect();
+                Task::batch(due.iter().map(|url| self.check_subscription(url)).collect::<Vec<_>>())
+            }
+            Message::CheckSubscriptionNow(url) => {
+                self.check_subscription(&url)
+            }
+            Message::SubscriptionListed(url, result) => {
+                self.subscriptions_checking.remove(&url);
+                let Some(index) = self.config.channel_subscriptions.iter().position(|s| s.url == url) else {
+                    return Task::none();
+                };
+                let videos = match result {
+                    Ok(videos) => videos,
+                    Err(e) => {
+                        self.config.channel_subscriptions[index].last_failed = Some(channel_subscriptions::now_secs());
+                        self.subscription_status.insert(url, format!("Error: {}", e));
+                        self.save_subscription_state();
+                        return Task::none();
+                    }
+                };
+                
+                let subscription = &mut self.config.channel_subscriptions[index];
+                let first_check = subscription.is_first_check();
+                subscription.last_checked = Some(channel_subscriptions::now_secs());
+                subscription.last_failed = None;
+                
+                let listed: HashSet<&str> = videos.iter().map(|video| video.video_id.as_str()).collect();
+                self.subscription_seen.retain_listed(&url, &listed);
+                
+                let new_items: Vec<VideoResult> = videos.iter()
+                    .filter(|video| !self.subscription_seen.contains(&url, &video.video_id))
+                    .filter(|video| !self.download_history.contains(&video.video_id))
+                    .cloned()
+                    .collect();
+                
+                if first_check {
+                    for video in &new_items {
+                        self.subscription_seen.mark(&url, &video.video_id);
+                    }
+                    self.subscription_status.insert(url, format!("Watching {} existing item(s) for new uploads", new_items.len()));
+                    self.save_subscription_state();
+                    return Task::none();
+                }
+                
+                self.subscription_status.insert(url.clone(), match new_items.len() {
+                    0 => "No new items".to_string(),
+                    count => format!("{} new item(s)", count),
+                });
+                
+                if subscription.auto_download {
+                    let subscription = subscription.clone();
+                    for video in &new_items {
+                        self.queue_subscription_item(&subscription, video);
+                        self.subscription_seen.mark(&url, &video.video_id);
+                    }
+                    self.save_subscription_state();
+                    return self.start_queued_downloads();
+                }
+                
+                // Waiting for approval; seen only once approved or dismissed
+                let pending = self.subscription_new.entry(url).or_default();
+                for video in new_items {
+                    if !pending.iter().any(|v| v.video_id == video.video_id) {
+                        pending.push(video);
+                    }
+                }
+                self.save_subscription_state();
+                Task::none()
+            }
+            Message::ApproveSubscriptionItem(url, video_id) => {
+                self.resolve_subscription_items(&url, Some(&video_id), true)
+            }
+            Message::DismissSubscriptionItem(url, video_id) => {
+                self.resolve_subscription_items(&url, Some(&video_id), false)
+            }
+            Message::ApproveAllSubscriptionItems(url) => {
+                self.resolve_subscription_items(&url, None, true)
+            }
+            Message::DismissAllSubscriptionItems(url) => {
+                self.resolve_subscription_items(&url, None, false)
+            }
+            Message::KeyBindingChanged(action, key) => {
+                *self.key_binding_inputs.get_mut(action) = key;
+                Task::none()
+            }
+            Message::InstallYtDlp => {
+                self.ytdlp_installing = true;
+                self.ytdlp_status = "Downloading yt-dlp...".to_string();
+                Task::perform(download_ytdlp(self.config.ytdlp_channel), Message::YtDlpInstalled)
+            }
+            Message::YtDlpInstalled(result) => {
+                self.ytdlp_installing = false;
+                self.refresh_ytdlp_files();
+                match result {
+                    Ok(version) => {
+                        self.ytdlp_status = format!("yt-dlp {} installed successfully!", version);
+                        return Task::perform(
+                            ytdlp_update::installed_version(get_ytdlp_path()),
+                            Message::YtDlpVersionLoaded,
+                        );
+                    }
+                    Err(e) => {
+                        self.ytdlp_status = format!("Installation failed: {}", e);
+                    }
+                }
+                Task::none()
+            }
+            Message::YtDlpVersionLoaded(version) => {
+                self.ytdlp_version = version;
+                self.refresh_ytdlp_files();
+                Task::none()
+            }
+            Message::YtDlpChannelSelected(channel) => {
+                self.config.ytdlp_channel = channel;
+                self.ytdlp_latest = None;
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(format!("Failed to save config: {}", e));
+                }
+                Task::none()
+            }
+            Message::CheckYtDlpUpdate => {
+                self.ytdlp_checking = true;
+                self.ytdlp_status = "Checking for updates...".to_string();
+                let channel = self.config.ytdlp_channel;
+                Task::perform(
+                    async move {
+                        ytdlp_update::fetch_latest_release(channel)
+                            .await
+                            .map(|release| release.version)
+                    },
+                    Message::YtDlpUpdateChecked,
+                )
+            }
+            Message::YtDlpUpdateChecked(result) => {
+                self.ytdlp_checking = false;
+                match result {
+                    Ok(latest) => {
+                        self.ytdlp_status = if self.ytdlp_version.as_deref() == Some(latest.as_str()) {
+                            "yt-dlp is up to date".to_string()
+                        } else {
+                            format!("Update available: {}", latest)
+                        };
+                        self.ytdlp_latest = Some(latest);
+                    }
+                    Err(e) => {
+                        self.ytdlp_status = format!("Update check failed: {}", e);
+                    }
+                }
+                Task::none()
+            }
+            Message::FfmpegVersionsLoaded(versions) => {
+                self.ffmpeg_versions = versions;
+                Task::none()
+            }
+            Message::PickFfmpeg => {
+                Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Select ffmpeg Binary")
+                            .pick_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    Message::FfmpegPicked,
+                )
+            }
+            Message::FfmpegPicked(path) => {
+                if let Some(path) = path {
+                    self.config.ffmpeg_path = Some(path);
+                    if let Err(e) = self.config.save() {
+                        self.error_message = Some(format!("Failed to save config: {}", e));
+                    }
+                    return self.refresh_ffmpeg();
+                }
+                Task::none()
+            }
+            Message::ClearFfmpegPath => {
+                self.config.ffmpeg_path = None;
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(format!("Failed to save config: {}", e));
+                }
+                self.refresh_ffmpeg()
+            }
+            Message::InstallFfmpeg => {
+                self.ffmpeg_installing = true;
+                self.ffmpeg_status = "Downloading ffmpeg...".to_string();
+                Task::perform(
+                    ffmpeg_install::install_static_build(get_local_bin_dir()),
+                    Message::FfmpegInstalled,
+                )
+            }
+            Message::FfmpegInstalled(result) => {
+                self.ffmpeg_installing = false;
+                match result {
+                    Ok(()) => {
+                        self.ffmpeg_status = "ffmpeg installed successfully!".to_string();
+                        return self.refresh_ffmpeg();
+                    }
+                    Err(e) => {
+                        self.ffmpeg_status = format!("Installation failed: {}", e);
+                    }
+                }
+                Task::none()
+            }
+            Message::ShowDiagnostics => {
+                self.show_diagnostics = true;
+                self.update(Message::RunDiagnostics)
+            }
+            Message::CloseDiagnostics => {
+                self.show_diagnostics = false;
+                Task::none()
+            }
+            Message::RunDiagnostics => {
+                self.diagnostics_running = true;
+                self.diagnostics_status = None;
+                let env = diagnostics::Environment {
+                    ytdlp: find_ytdlp(),
+                    ffmpeg_location: find_ffmpeg(self.config.ffmpeg_path.as_deref()),
+                    download_directory: self.config.download_directory.clone(),
+                };
+                Task::perform(diagnostics::run_checks(env), Message::DiagnosticsCompleted)
+            }
+            Message::DiagnosticsCompleted(checks) => {
+                self.diagnostics_running = false;
+                self.diagnostics = checks;
+                Task::none()
+            }
+            Message::CopyDiagnosticsReport => {
+                let report = diagnostics::format_report(&self.diagnostics);
+                let result = self.copy_to_clipboard(report);
+                
+                self.diagnostics_status = Some(match result {
+                    Ok(()) => "Report copied to clipboard".to_string(),
+                    Err(e) => format!("Failed to copy report: {}", e),
+                });
+                Task::none()
+            }
+            Message::SaveDiagnosticsReport => {
+                Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Save Diagnostics Report")
+                            .set_file_name("nightingale-diagnostics.txt")
+                            .save_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    Message::DiagnosticsReportPathPicked,
+                )
+            }
+            Message::DiagnosticsReportPathPicked(path) => {
+                if let Some(path) = path {
+                    let report = diagnostics::format_report(&self.diagnostics);
+                    self.diagnostics_status = Some(match std::fs::write(&path, report) {
+                        Ok(()) => format!("Report saved to {}", path.display()),
+                        Err(e) => format!("Failed to save report: {}", e),
+                    });
+                }
+                Task::none()
+            }
+            Message::CookieBrowserSelected(browser) => {
+                self.config.browser_for_cookies = if browser == NO_COOKIE_BROWSER {
+                    None
+                } else {
+                    Some(browser)
+                };
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(format!("Failed to save config: {}", e));
+                }
+                Task::none()
+            }
+            Message::CookieProfileChanged(profile) => {
+                self.cookie_profile_input = profile;
+                Task::none()
+            }
+            Message::SettingsInputSubmitted => {
+                self.save_settings_inputs();
+                Task::none()
+            }
+            Message::PickCookiesFile => {
+                Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Select cookies.txt")
+                            .add_filter("Cookies file", &["txt"])
+                            .pick_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    Message::CookiesFilePicked,
+                )
+            }
+            Message::CookiesFilePicked(path) => {
+                if let Some(path) = path {
+                    self.config.cookies_file = Some(path);
+                    if let Err(e) = self.config.save() {
+                        self.error_message = Some(format!("Failed to save config: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            Message::ClearCookiesFile => {
+                self.config.cookies_file = None;
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(format!("Failed to save config: {}", e));
+                }
+                Task::none()
+            }
+            Message::RetryClientChanged(index, client) => {
+                if let Some(strategy) = self.retry_strategy_inputs.get_mut(index) {
+                    strategy.player_client = client;
+                }
+                Task::none()
+            }
+            Message::RetryFormatChanged(index, format) => {
+                if let Some(strategy) = self.retry_strategy_inputs.get_mut(index) {
+                    strategy.format = if format.is_empty() { None } else { Some(format) };
+                }
+                Task::none()
+            }
+            Message::AddRetryStrategy => {
+                self.retry_strategy_inputs.push(RetryStrategy {
+                    player_client: "web".to_string(),
+                    format: None,
+                });
+                self.save_settings_inputs();
+                Task::none()
+            }
+            Message::RemoveRetryStrategy(index) => {
+                if index < self.retry_strategy_inputs.len() {
+                    self.retry_strategy_inputs.remove(index);
+                }
+                self.save_settings_inputs();
+                Task::none()
+            }
+            Message::RetryDelayChanged(value) => {
+                self.retry_delay_input = value.chars().filter(|c| c.is_ascii_digit()).collect();
+                Task::none()
+            }
+            Message::RollbackYtDlp => {
+                let result = ytdlp_update::rollback(&get_ytdlp_path());
+                self.refresh_ytdlp_files();
+                match result {
+                    Ok(()) => {
+                        self.ytdlp_status = "Rolled back to the previous yt-dlp".to_string();
+                        self.ytdlp_latest = None;
+                        return Task::perform(
+                            ytdlp_update::installed_version(get_ytdlp_path()),
+                            Message::YtDlpVersionLoaded,
+                        );
+                    }
+                    Err(e) => {
+                        self.ytdlp_status = format!("Rollback failed: {}", e);
+                    }
+                }
+                Task::none()
+            }
+            Message::PlayFile(video_id) => {
+                if !self.verify_downloaded_file(&video_id) {
+                    return Task::none();
+                }
+                if let Some(file_path) = self.downloaded_files.get(&video_id) {
+                    let _ = open::that(file_path);
+                }
+                Task::none()
+            }
+            Message::ToggleChapters(video_id) => {
+                if self.expanded_chapters.remove(&video_id) {
+                    return Task::none();
+                }
+                self.expanded_chapters.insert(video_id.clone());
+                
+                // Only look the chapters up once; a failed lookup can be retried by toggling again
+                let failed = self.chapters_status.get(&video_id).is_some_and(|status| status.starts_with("Error"));
+                if self.video_chapters.contains_key(&video_id) || (self.chapters_status.contains_key(&video_id) && !failed) {
+                    return Task::none();
+                }
+                self.chapters_status.insert(video_id.clone(), "Loading chapters...".to_string());
+                Task::perform(
+                    chapters::fetch(video_id.clone(), self.config.cookie_args()),
+                    move |result| Message::ChaptersLoaded(video_id.clone(), result),
+                )
+            }
+            Message::ChaptersLoaded(video_id, result) => {
+                match result {
+                    Ok(chapters) if chapters.is_empty() => {
+                        self.chapters_status.insert(video_id, "This video has no chapters".to_string());
+                    }
+                    Ok(chapters) => {
+                        self.chapters_status.remove(&video_id);
+                        self.video_chapters.insert(video_id, chapters);
+                    }
+                    Err(e) => {
+                        self.chapters_status.insert(video_id, format!("Error: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            Message::ChaptersSplit(video_id, result) => {
+                let message = match result {
+                    Ok(tracks) => {
+                        let folder = tracks.first()
+                            .and_then(|track| track.parent())
+                            .map(|folder| folder.display().to_string())
+                            .unwrap_or_default();
+                        format!("Split into {} chapter files in {}", tracks.len(), folder)
+                    }
+                    Err(e) => format!("Error splitting chapters: {}", e),
+                };
+                self.download_messages.insert(video_id, message);
+                Task::none()
+            }
+            Message::LoudnessProcessed(video_id, result) => {
+                match result {
+                    Ok(loudness) => {
+                        self.download_messages.insert(video_id.clone(), loudness.describe());
+                        self.download_history.set_loudness(&video_id, loudness);
+                        if let Err(e) = self.download_history.save() {
+                            self.error_message = Some(format!("Failed to save download history: {}", e));
+                        }
+                    }
+                    Err(e) => {
+                        self.download_messages.insert(video_id.clone(), format!("Error adjusting loudness: {}", e));
+                    }
+                }
+                
+                match self.downloaded_files.get(&video_id).cloned() {
+                    Some(file_path) => self.post_process(video_id, file_path, PostStep::Lyrics),
+                    None => Task::none(),
+                }
+            }
+            Message::LyricsProcessed(video_id, result) => {
+                let message = match result {
+                    Ok(summary) => summary,
+                    Err(e) => format!("No lyrics: {}", e),
+                };
+                self.download_messages.insert(video_id.clone(), message);
+                
+                match self.downloaded_files.get(&video_id).cloned() {
+                    Some(file_path) => self.post_process(video_id, file_path, PostStep::Chapters),
+                    None => Task::none(),
+                }
+            }
+            Message::ShowTrimEditor(video_id) => {
+                if !self.verify_downloaded_file(&video_id) {
+                    return Task::none();
+                }
+                let Some(file_path) = self.downloaded_files.get(&video_id).cloned() else {
+                    return Task::none();
+                };
+                let Some(ffmpeg) = self.ffmpeg_binary() else {
+                    self.error_message = Some("ffmpeg is needed to trim files; install it from Settings".to_string());
+                    return Task::none();
+                };
+                
+                self.trim_editor = Some(TrimEditor {
+                    file_path: file_path.clone(),
+                    waveform: None,
+                    start: 0.0,
+                    end: 0.0,
+                    fade_in: "0".to_string(),
+                    fade_out: "0".to_string(),
+                    busy: true,
+                    status: Some("Reading audio...".to_string()),
+                });
+                Task::perform(trim::load_waveform(ffmpeg, file_path), Message::WaveformLoaded)
+            }
+            Message::WaveformLoaded(result) => {
+                if let Some(editor) = &mut self.trim_editor {
+                    editor.busy = false;
+                    match result {
+                        Ok(waveform) => {
+                            editor.start = 0.0;
+                            editor.end = waveform.duration;
+                            editor.waveform = Some(waveform);
+                            editor.status = None;
+                        }
+                        Err(e) => editor.status = Some(e),
+                    }
+                }
+                Task::none()
+            }
+            Message::TrimStartChanged(start) => {
+                if let Some(editor) = &mut self.trim_editor {
+                    editor.start = start.min(editor.end);
+                }
+                Task::none()
+            }
+            Message::TrimEndChanged(end) => {
+                if let Some(editor) = &mut self.trim_editor {
+                    editor.end = end.max(editor.start);
+                }
+                Task::none()
+            }
+            Message::TrimFadeInChanged(value) => {
+                if let Some(editor) = &mut self.trim_editor {
+                    editor.fade_in = value;
+                }
+                Task::none()
+            }
+            Message::TrimFadeOutChanged(value) => {
+                if let Some(editor) = &mut self.trim_editor {
+                    editor.fade_out = value;
+                }
+                Task::none()
+            }
+            Message::ApplyTrim => {
+                let ffmpeg = self.ffmpeg_binary();
+                let Some(editor) = &mut self.trim_editor else {
+                    return Task::none();
+                };
+                let Some(ffmpeg) = ffmpeg else {
+                    editor.status = Some("ffmpeg not found".to_string());
+                    return Task::none();
+                };
+                let (Some(fade_in), Some(fade_out)) = (trim::parse_time(&editor.fade_in), trim::parse_time(&editor.fade_out)) else {
+                    editor.status = Some("Fades must be a number of seconds".to_string());
+                    return Task::none();
+                };
+                
+                let settings = trim::TrimSettings {
+                    start: editor.start,
+                    end: editor.end,
+                    fade_in,
+                    fade_out,
+                };
+                editor.busy = true;
+                editor.status = Some("Re-encoding...".to_string());
+                Task::perform(trim::apply_trim(ffmpeg, editor.file_path.clone(), settings), Message::TrimApplied)
+            }
+            Message::TrimApplied(result) => {
+                let ffmpeg = self.ffmpeg_binary();
+                let Some(editor) = &mut self.trim_editor else {
+                    return Task::none();
+                };
+                match (result, ffmpeg) {
+                    // Show the new, shorter file so further trims start from it
+                    (Ok(()), Some(ffmpeg)) => {
+                        editor.status = Some("Trimmed".to_string());
+                        Task::perform(trim::load_waveform(ffmpeg, editor.file_path.clone()), Message::WaveformLoaded)
+                    }
+                    (Ok(()), None) => {
+                        editor.busy = false;
+                        editor.status = Some("Trimmed".to_string());
+                        Task::none()
+                    }
+                    (Err(e), _) => {
+                        editor.busy = false;
+                        editor.status = Some(e);
+                        Task::none()
+                    }
+                }
+            }
+            Message::CloseTrimEditor => {
+                // Closing mid-encode would lose track of the result; wait for it
+                if !self.trim_editor.as_ref().is_some_and(|editor| editor.busy) {
+                    self.trim_editor = None;
+                }
+                Task::none()
+            }
+            Message::ShareFile(video_id) => {
+                if !self.verify_downloaded_file(&video_id) {
+                    return Task::none();
+                }
+                
+                // Get the file path for this video
+                if let Some(file_path) = self.downloaded_files.get(&video_id) {
+                    #[cfg(target_os = "macos")]
+                    {
+                        // On macOS, try to use AirDrop
+                        match macos_share::share_file_via_airdrop(file_path) {
+                            Ok(()) => {
+                                // AirDrop picker shown successfully
+                                return Task::none();
+                            }
+                            Err(e) => {
+                                // Fall back to Wi-Fi share on error
+                                eprintln!("AirDrop failed: {}, falling back to Wi-Fi share", e);
+                            }
+                        }
+                    }
+                    
+                    // On Linux or if macOS AirDrop fails, use Wi-Fi share
+                    let file_path = file_path.clone();
+                    self.open_share_modal(&[file_path]);
+                }
+                Task::none()
+            }
+            Message::CloseShare => {
+                if let Some(modal) = self.share_modal.take() {
+                    if let Some(server) = modal.server {
+                        server.stop();
+                    }
+                    
+                    // Remember the Wi-Fi details for the next share
+                    if modal.qr_mode == QrMode::WifiJoin {
+                        if let Err(e) = self.config.save() {
+                            self.error_message = Some(format!("Failed to save config: {}", e));
+                        }
+                    }
+                }
+                Task::none()
+            }
+            Message::ToggleQrMode => {
+                if let Some(modal) = &mut self.share_modal {
+                    modal.qr_mode = match modal.qr_mode {
+                        QrMode::Url => QrMode::WifiJoin,
+                        QrMode::WifiJoin => QrMode::Url,
+                    };
+                    
+                    if modal.qr_mode == QrMode::WifiJoin && self.config.wifi_ssid.is_none() {
+                        self.config.wifi_ssid = wifi_share::current_wifi_ssid();
+                    }
+                    
+                    modal.refresh_qr(&self.config);
+                }
+                Task::none()
+            }
+            Message::WifiSsidChanged(ssid) => {
+                self.config.wifi_ssid = Some(ssid);
+                if let Some(modal) = &mut self.share_modal {
+                    modal.refresh_qr(&self.config);
+                }
+                Task::none()
+            }
+            Message::WifiPasswordChanged(password) => {
+                self.config.wifi_password = Some(password);
+                if let Some(modal) = &mut self.share_modal {
+                    modal.refresh_qr(&self.config);
+                }
+                Task::none()
+            }
+            Message::SaveQrCode => {
+                Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Save QR Code")
+                            .set_file_name("nightingale-qr.png")
+                            .add_filter("PNG image", &["png"])
+                            .save_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    Message::QrSavePathPicked,
+                )
+            }
+            Message::QrSavePathPicked(path) => {
+                if let (Some(path), Some(modal)) = (path, &mut self.share_modal) {
+                    if let Some(qr) = &modal.qr_image {
+                        modal.qr_status = Some(match qr.save_png(&path) {
+                            Ok(()) => format!("Saved to {}", path.display()),
+                            Err(e) => e,
+                        });
+                    }
+                }
+                Task::none()
+            }
+            Message::CopyQrCode => {
+                let Some(qr) = self.share_modal.as_ref().and_then(|modal| modal.qr_image.clone()) else {
+                    return Task::none();
+                };
+                let image = arboard::ImageData {
+                    width: qr.width as usize,
+                    height: qr.height as usize,
+                    bytes: qr.pixels.into(),
+                };
+                let result = self.clipboard()
+                    .and_then(|clipboard| clipboard.set_image(image).map_err(|e| e.to_string()));
+                
+                if let Some(modal) = &mut self.share_modal {
+                    modal.qr_status = Some(match result {
+                        Ok(()) => "QR code copied to clipboard".to_string(),
+                        Err(e) => format!("Failed to copy QR code: {}", e),
+                    });
+                }
+                Task::none()
+            }
+            Message::PollPhoneRequests => {
+                if let Some(server) = self.share_modal.as_ref().and_then(|m| m.server.as_ref()) {
+                    self.phone_requests.extend(server.take_requests());
+                }
+                self.sync_held_phone_requests();
+                Task::none()
+            }
+            Message::ApprovePhoneRequest(index) => {
+                if index >= self.phone_requests.len() {
+                    return Task::none();
+                }
+                
+                if self.config.download_directory.is_none() {
+                    self.show_settings = true;
+                    self.error_message = Some("Please select a download directory in settings".to_string());
+                    return Task::none();
+                }
+                
+                let input = self.phone_requests.remove(index);
+                self.sync_held_phone_requests();
+                let cookie_args = self.config.cookie_args();
+                Task::perform(
+                    async move {
+                        search_youtube(&input, &cookie_args).await
+                    },
+                    Message::PhoneRequestResolved,
+                )
+            }
+            Message::DismissPhoneRequest(index) => {
+                if index < self.phone_requests.len() {
+                    self.phone_requests.remove(index);
+                    self.sync_held_phone_requests();
+                }
+                Task::none()
+            }
+            Message::PhoneRequestResolved(result) => {
+                match result {
+                    Ok(results) => {
+                        // Queue the best match
+                        if let Some(video) = results.into_iter().next() {
+                            let filename = clean_filename(&video.title);
+                            return self.queue_found_video(video, filename);
+                        }
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Phone request failed: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            Message::SearchPlaylistLink(whole_playlist) => {
+                let Some(YouTubeLink::VideoInPlaylist { id, list, start }) = self.playlist_choice.take() else {
+                    return Task::none();
+                };
+                
+                self.search_query = if whole_playlist {
+                    youtube_url::playlist_url(&list)
+                } else {
+                    if let Some(start) = start {
+                        self.link_starts.insert(id.clone(), start);
+                    }
+                    youtube_url::video_url(&id)
+                };
+                self.update(Message::SearchPressed)
+            }
+            Message::DismissPlaylistChoice => {
+                self.playlist_choice = None;
+                Task::none()
+            }
+            Message::ClipboardWatchToggled(enabled) => {
+                self.config.clipboard_watch = enabled;
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(format!("Failed to save config: {}", e));
+                }
+                // Start from whatever is on the clipboard now
+                self.clipboard_seen = None;
+                self.clipboard_link = None;
+                Task::none()
+            }
+            Message::PollClipboard => {
+                let contents = match self.clipboard() {
+                    Ok(clipboard) => clipboard.get_text().map(|text| text.trim().to_string()).unwrap_or_default(),
+                    Err(_) => return Task::none(),
+                };
+                if self.clipboard_seen.as_deref() == Some(contents.as_str()) {
+                    return Task::none();
+                }
+                
+                // The first look only notes what was copied before the watcher started
+                let first_look = self.clipboard_seen.is_none();
+                self.clipboard_seen = Some(contents.clone());
+                // Links only; a copied 11-character word could pass for a bare video id
+                let is_link = YouTubeLink::parse_url(&contents).is_some_and(|link| !matches!(link, YouTubeLink::Search(_)));
+                if !first_look && is_link {
+                    self.clipboard_link = Some(contents);
+                }
+                Task::none()
+            }
+            Message::SearchClipboardLink => {
+                let Some(link) = self.clipboard_link.take() else {
+                    return Task::none();
+                };
+                self.search_query = link;
+                Task::done(Message::SearchPressed)
+            }
+            Message::DownloadClipboardLink => {
+                if self.config.download_directory.is_none() {
+                    self.show_settings = true;
+                    self.error_message = Some("Please select a download directory in settings".to_string());
+                    return Task::none();
+                }
+                
+                let Some(link) = self.clipboard_link.take() else {
+                    return Task::none();
+                };
+                let cookie_args = self.config.cookie_args();
+                Task::perform(
+                    async move { search_youtube(&link, &cookie_args).await },
+                    Message::ClipboardLinkResolved,
+                )
+            }
+            Message::DismissClipboardLink => {
+                self.clipboard_link = None;
+                Task::none()
+            }
+            Message::ClipboardLinkResolved(result) => {
+                match result {
+                    Ok(results) => {
+                        if let Some(video) = results.into_iter().next() {
+                            let filename = clean_filename(&video.title);
+                            return self.queue_found_video(video, filename);
+                        }
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Failed to get the copied link: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            Message::ApiToggled(enabled) => {
+                self.config.api_enabled = enabled;
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(format!("Failed to save config: {}", e));
+                }
+                if enabled {
+                    self.start_api_server();
+                } else {
+                    self.api_server = None;
+                    self.api_error = None;
+                }
+                Task::none()
+            }
+            Message::ApiPortChanged(value) => {
+                if let Ok(port) = value.trim().parse::<u16>() {
+                    if port > 0 {
+                        self.config.api_port = port;
+                        if let Err(e) = self.config.save() {
+                            self.error_message = Some(format!("Failed to save config: {}", e));
+                        }
+                    }
+                }
+                Task::none()
+            }
+            Message::CopyApiToken => {
+                if let Err(e) = self.copy_to_clipboard(self.config.api_token.clone()) {
+                    self.api_error = Some(format!("Failed to copy token: {}", e));
+                }
+                Task::none()
+            }
+            Message::RegenerateApiToken => {
+                // Callers holding the old token are locked out straight away
+                match api_server::generate_token() {
+                    Ok(token) => self.config.api_token = token,
+                    Err(e) => {
+                        self.api_error = Some(e);
+                        return Task::none();
+                    }
+                }
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(format!("Failed to save config: {}", e));
+                }
+                if let Some(server) = &self.api_server {
+                    server.set_token(self.config.api_token.clone());
+                }
+                Task::none()
+            }
+            Message::ApiRequest(request) => {
+                self.handle_api_request(request)
+            }
+            Message::ApiSearchCompleted(reply, result) => {
+                reply.send(match result {
+                    Ok(results) => ApiResponse::ok(serde_json::json!({
+                        "results": results.iter().map(VideoResult::to_json).collect::<Vec<_>>(),
+                    })),
+                    Err(e) => ApiResponse::error(502, e),
+                });
+                Task::none()
+            }
+            Message::ApiEnqueueResolved(reply, filename, queue_all, result) => {
+                let results = match result {
+                    Ok(results) => results,
+                    Err(e) => {
+                        reply.send(ApiResponse::error(502, e));
+                        return Task::none();
+                    }
+                };
+                
+                // A playlist queues everything it lists; a video or search queues its best match
+                let videos: Vec<VideoResult> = if queue_all {
+                    results
+                } else {
+                    results.into_iter().take(1).collect()
+                };
+                let filename = filename.filter(|_| videos.len() == 1);
+                
+                let mut tasks = Vec::new();
+                let mut queued = Vec::new();
+                for video in videos {
+                    let filename = clean_filename(filename.as_deref().unwrap_or(&video.title));
+                    queued.push(serde_json::json!({
+                        "video_id": video.video_id,
+                        "title": video.title,
+                        "filename": filename,
+                    }));
+                    
+                    tasks.push(self.queue_found_video(video, filename));
+                }
+                
+                reply.send(ApiResponse::accepted(serde_json::json!({ "queued": queued })));
+                Task::batch(tasks)
+            }
+            Message::InitTray => {
+                match Tray::new() {
+                    Ok(tray) => self.tray = Some(tray),
+                    Err(e) => self.tray_error = Some(e),
+                }
+                self.refresh_tray();
+                Task::none()
+            }
+            Message::PollTray => {
+                let events = self.tray.as_ref().map(|tray| tray.take_events()).unwrap_or_default();
+                let mut tasks = Vec::new();
+                
+                for event in events {
+                    match event {
+                        TrayEvent::ShowWindow => {
+                            tasks.push(window::get_latest().and_then(|id| {
+                                window::change_mode::<Message>(id, window::Mode::Windowed)
+                                    .chain(window::gain_focus(id))
+                            }));
+                        }
+                        TrayEvent::Quit => return iced::exit(),
+                    }
+                }
+                Task::batch(tasks)
+            }
+            Message::WindowCloseRequested(id) => {
+                // Keep downloads going in the background when the tray can bring the window back
+                if self.tray.is_some() && self.config.minimize_to_tray {
+                    window::change_mode(id, window::Mode::Hidden)
+                } else {
+                    iced::exit()
+                }
+            }
+            Message::NotificationsToggled(enabled) => {
+                self.config.notifications_enabled = enabled;
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(format!("Failed to save config: {}", e));
+                }
+                Task::none()
+            }
+            Message::MinimizeToTrayToggled(enabled) => {
+                self.config.minimize_to_tray = enabled;
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(format!("Failed to save config: {}", e));
+                }
+                Task::none()
+            }
+            Message::LoudnessModeSelected(mode) => {
+                self.config.loudness_mode = mode;
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(format!("Failed to save config: {}", e));
+                }
+                Task::none()
+            }
+            Message::LoudnessTargetChanged(value) => {
+                // Keep what's typed and only take it once it's a usable target
+                if let Some(target) = loudness::parse_target(&value) {
+                    self.config.loudness_target_lufs = target;
+                    if let Err(e) = self.config.save() {
+                        self.error_message = Some(format!("Failed to save config: {}", e));
+                    }
+                }
+                self.loudness_target_input = value;
+                Task::none()
+            }
+            Message::LoudnessTargetSubmitted => {
+                if loudness::parse_target(&self.loudness_target_input).is_none() {
+                    self.error_message = Some(format!(
+                        "The loudness target must be between {} and {} LUFS",
+                        loudness::MIN_TARGET_LUFS,
+                        loudness::MAX_TARGET_LUFS
+                    ));
+                }
+                // Show the target in use, tidied up or restored after a bad entry
+                self.loudness_target_input = format!("{}", -self.config.loudness_target_lufs);
+                Task::none()
+            }
+            Message::SponsorBlockToggled(enabled) => {
+                self.config.sponsorblock_enabled = enabled;
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(format!("Failed to save config: {}", e));
+                }
+                Task::none()
+            }
+            Message::SponsorBlockUrlChanged(url) => {
+                self.sponsorblock_url_input = url;
+                Task::none()
+            }
+            Message::SponsorBlockCategoryToggled(category, enabled) => {
+                self.config.sponsorblock_categories.retain(|c| c != category);
+                if enabled {
+                    self.config.sponsorblock_categories.push(category.to_string());
+                }
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(format!("Failed to save config: {}", e));
+                }
+                Task::none()
+            }
+
+        }
+    }
+    
+    fn subscription(&self) -> Subscription<Message> {
+        let keyboard_events = event::listen_with(|event, status, _window| {
+            let event::Event::Keyboard(keyboard_event) = event else {
+                return None;
+            };
+            
+            // The search box swallows Escape, which should still close the history dropdown
+            let escape = matches!(
+                &keyboard_event,
+                keyboard::Event::KeyPressed { key: keyboard::Key::Named(keyboard::key::Named::Escape), .. }
+            );
+            
+            match status {
+                event::Status::Ignored => Some(Message::KeyboardEvent(keyboard_event)),
+                event::Status::Captured if escape => Some(Message::KeyboardEvent(keyboard_event)),
+                event::Status::Captured => None,
+            }
+        });
+        
+        // Only poll for phone requests while the share server is running
+        let sharing = self.share_modal.as_ref().is_some_and(|m| m.server.is_some());
+        let phone_requests = if sharing {
+            iced::time::every(Duration::from_millis(500)).map(|_| Message::PollPhoneRequests)
+        } else {
+            Subscription::none()
+        };
+        
+        let tray_events = if self.tray.is_some() {
+            iced::time::every(Duration::from_millis(250)).map(|_| Message::PollTray)
+        } else {
+            Subscription::none()
+        };
+        
+        let close_requests = window::close_requests().map(Message::WindowCloseRequested);
+        
+        // Each subscription keeps its own schedule; this only looks for ones that are due
+        let subscription_checks = if self.config.channel_subscriptions.is_empty() {
+            Subscription::none()
+        } else {
+            iced::time::every(Duration::from_secs(60)).map(|_| Message::CheckSubscriptions)
+        };
+        
+        let clipboard_checks = if self.config.clipboard_watch {
+            iced::time::every(Duration::from_secs(1)).map(|_| Message::PollClipboard)
+        } else {
+            Subscription::none()
+        };
+        
+        // Delivered as they arrive rather than polled, so an idle app isn't redrawn
+        let api_requests = match &self.api_server {
+            Some(server) => Subscription::run_with_id(server.id(), server.requests()).map(Message::ApiRequest),
+            None => Subscription::none(),
+        };
+        
+        Subscription::batch([keyboard_events, phone_requests, tray_events, close_requests, subscription_checks, clipboard_checks, api_requests])
+    }
+    
+    // List a subscription's playlist or channel, unless that's already under way
+    fn check_subscription(&mut self, url: &str) -> Task<Message> {
+        let Some(subscription) = self.config.channel_subscriptions.iter().find(|s| s.url == url) else {
+            return Task::none();
+        };
+        if !self.subscriptions_checking.insert(url.to_string()) {
+            return Task::none();
+        }
+        
+        self.subscription_status.insert(url.to_string(), "Checking...".to_string());
+        let listing_url = subscription.listing_url();
+        let cookie_args = self.config.cookie_args();
+        let url = url.to_string();
+        Task::perform(
+            async move { get_video_info_from_url(&listing_url, &cookie_args).await },
+            move |result| Message::SubscriptionListed(url.clone(), result),
+        )
+    }
+    
+    // Save when subscriptions were last checked and what they have listed
+    fn save_subscription_state(&mut self) {
+        if let Err(e) = self.config.save() {
+            self.error_message = Some(format!("Failed to save config: {}", e));
+        }
+        if let Err(e) = self.subscription_seen.save() {
+            self.error_message = Some(format!("Failed to save subscriptions: {}", e));
+        }
+    }
+    
+    // Queue an item from a subscription with that subscription's folder and format
+    fn queue_subscription_item(&mut self, subscription: &ChannelSubscription, video: &VideoResult) {
+        self.enqueue_download(QueuedDownload {
+            output_dir: subscription.output_dir.clone(),
+            format: subscription.format,
+            ..QueuedDownload::new(video.video_id.clone(), clean_filename(&video.title))
+        });
+        
+        self.subscription_downloads.retain(|(video_id, _)| video_id != &video.video_id);
+        self.subscription_downloads.insert(0, (video.video_id.clone(), video.title.clone()));
+        self.subscription_downloads.truncate(MAX_RECENT_SUBSCRIPTION_DOWNLOADS);
+    }
+    
+    // Download or dismiss one pending item, or all of them when video_id is None
+    fn resolve_subscription_items(&mut self, url: &str, video_id: Option<&str>, download: bool) -> Task<Message> {
+        let Some(index) = self.config.channel_subscriptions.iter().position(|s| s.url == url) else {
+            return Task::none();
+        };
+        let Some(pending) = self.subscription_new.get_mut(url) else {
+            return Task::none();
+        };
+        
+        let (chosen, remaining): (Vec<VideoResult>, Vec<VideoResult>) = std::mem::take(pending)
+            .into_iter()
+            .partition(|video| video_id.is_none_or(|id| video.video_id == id));
+        if remaining.is_empty() {
+            self.subscription_new.remove(url);
+        } else {
+            *pending = remaining;
+        }
+        
+        let subscription = self.config.channel_subscriptions[index].clone();
+        for video in &chosen {
+            self.subscription_seen.mark(url, &video.video_id);
+            if download {
+                self.queue_subscription_item(&subscription, video);
+            }
+        }
+        self.save_subscription_state();
+        
+        if download {
+            self.start_queued_downloads()
+        } else {
+            Task::none()
+        }
+    }
+    
+    // Add a download to the queue; it starts once a download slot is free
+    fn enqueue_download(&mut self, download: QueuedDownload) {
+        let already_pending = self.downloading.get(&download.video_id).copied().unwrap_or(false)
+            || self.download_queue.iter().any(|d| d.video_id == download.video_id);
+        if already_pending {
+            return;
+        }
+        
+        self.download_messages.insert(download.video_id.clone(), "Queued".to_string());
+        self.download_queue.push_back(download);
+    }
+    
+    // Check a downloaded file is still on disk, forgetting it if it was moved or deleted
+    fn verify_downloaded_file(&mut self, video_id: &str) -> bool {
+        match self.downloaded_files.get(video_id) {
+            Some(path) if path.exists() => true,
+            Some(path) => {
+                self.download_messages.insert(
+                    video_id.to_string(),
+                    format!("Error: {} no longer exists", path.display()),
+                );
+                self.downloaded_files.remove(video_id);
+                false
+            }
+            None => false,
+        }
+    }
+    
+    // Measure a finished download and normalize or tag it, when enabled in Settings
+    fn loudness_task(&mut self, video_id: String, file_path: PathBuf) -> Option<Task<Message>> {
+        let mode = self.config.loudness_mode;
+        if mode == LoudnessMode::Off {
+            return None;
+        }
+        let Some(ffmpeg) = self.ffmpeg_binary() else {
+            self.download_messages.insert(video_id, "Error adjusting loudness: ffmpeg not found".to_string());
+            return None;
+        };
+        
+        self.download_messages.insert(video_id.clone(), "Measuring loudness...".to_string());
+        Some(Task::perform(
+            loudness::process(ffmpeg, file_path, mode, self.config.loudness_target_lufs.clamp(loudness::MIN_TARGET_LUFS, loudness::MAX_TARGET_LUFS)),
+            move |result| Message::LoudnessProcessed(video_id.clone(), result),
+        ))
+    }
+    
+    // Run the first post-processing step from `from` on that applies to the download.
+    // Loudness comes before lyrics, which are written into the tags it rewrites, and
+    // both come before splitting so the chapter files get them too
+    fn post_process(&mut self, video_id: String, file_path: PathBuf, from: PostStep) -> Task<Message> {
+        if from <= PostStep::Loudness {
+            if let Some(task) = self.loudness_task(video_id.clone(), file_path.clone()) {
+                return task;
+            }
+        }
+        if from <= PostStep::Lyrics {
+            if let Some(task) = self.lyrics_task(video_id.clone(), file_path.clone()) {
+                return task;
+            }
+        }
+        if self.split_chapters.remove(&video_id) {
+            return self.split_chapters_task(video_id, file_path);
+        }
+        Task::none()
+    }
+    
+    fn lyrics_task(&mut self, video_id: String, file_path: PathBuf) -> Option<Task<Message>> {
+        let options = self.lyrics_requests.remove(&video_id)?;
+        
+        self.download_messages.insert(video_id.clone(), "Fetching lyrics...".to_string());
+        Some(Task::perform(
+            lyrics::fetch(
+                video_id.clone(),
+                file_path,
+                options,
+                self.config.cookie_args(),
+                find_ffmpeg(self.config.ffmpeg_path.as_deref()),
+            ),
+            move |result| Message::LyricsProcessed(video_id.clone(), result),
+        ))
+    }
+    
+    // Cut a finished download into chapter files, looking the chapters up first if needed
+    fn split_chapters_task(&mut self, video_id: String, file_path: PathBuf) -> Task<Message> {
+        let Some(ffmpeg) = self.ffmpeg_binary() else {
+            self.download_messages.insert(video_id, "Error splitting chapters: ffmpeg not found".to_string());
+            return Task::none();
+        };
+        
+        self.download_messages.insert(video_id.clone(), "Splitting into chapters...".to_string());
+        let known = self.video_chapters.get(&video_id).cloned();
+        let cookie_args = self.config.cookie_args();
+        let album = self.download_title(&video_id);
+        let vid_id = video_id.clone();
+        
+        Task::perform(
+            async move {
+                let chapters = match known {
+                    Some(chapters) => chapters,
+                    None => chapters::fetch(video_id, cookie_args).await?,
+                };
+                if chapters.is_empty() {
+                    return Err("This video has no chapters".to_string());
+                }
+                chapters::split(ffmpeg, file_path, chapters, album).await
+            },
+            move |result| Message::ChaptersSplit(vid_id.clone(), result),
+        )
+    }
+    
+    // The ffmpeg binary itself, wherever the configured or found location points
+    fn ffmpeg_binary(&self) -> Option<PathBuf> {
+        find_ffmpeg(self.config.ffmpeg_path.as_deref())
+            .map(|location| ffmpeg_install::binaries(std::path::Path::new(&location)).0)
+    }
+    
+    fn download_options(&self) -> DownloadOptions {
+        DownloadOptions {
+            ffmpeg_location: find_ffmpeg(self.config.ffmpeg_path.as_deref()),
+            cookie_args: self.config.cookie_args(),
+            retry_strategies: self.config.retry_strategies.clone(),
+            retry_base_delay_secs: self.config.retry_base_delay_secs,
+            download_section: None,
+            split_chapters: false,
+            audio_format: AudioFormat::default(),
+            sponsorblock: self.config.sponsorblock_enabled
+                .then(|| SponsorBlock {
+                    api_url: self.config.sponsorblock_api_url.clone(),
+                    categories: self.config.sponsorblock_categories.clone(),
+                })
+                .filter(|sponsorblock| !sponsorblock.categories.is_empty()),
+        }
+    }
+    
+    // Start as many queued downloads as there are free slots
+    fn start_queued_downloads(&mut self) -> Task<Message> {
+        let mut tasks = Vec::new();
+        let mut active = self.downloading.values().filter(|d| **d).count();
+        let mut waiting = VecDeque::new();
+        
+        while active < MAX_CONCURRENT_DOWNLOADS {
+            let Some(download) = self.download_queue.pop_front() else {
+                break;
+            };
+            // Items without a folder of their own wait until a download directory is chosen
+            if download.output_dir.is_none() && self.config.download_directory.is_none() {
+                waiting.push_back(download);
+                continue;
+            }
+            active += 1;
+            tasks.push(self.start_download(download));
+        }
+        
+        while let Some(download) = waiting.pop_back() {
+            self.download_queue.push_front(download);
+        }
+        
+        self.refresh_tray();
+        Task::batch(tasks)
+    }
+    
+    // Apply what's typed into the Settings text fields, saving the config once if anything changed
+    fn save_settings_inputs(&mut self) {
+        let mut changed = false;
+        
+        let profile = Some(self.cookie_profile_input.trim().to_string()).filter(|p| !p.is_empty());
+        if profile != self.config.browser_profile {
+            self.config.browser_profile = profile;
+            changed = true;
+        }
+        
+        if self.retry_strategy_inputs != self.config.retry_strategies {
+            self.config.retry_strategies = self.retry_strategy_inputs.clone();
+            changed = true;
+        }
+        
+        let delay = self.retry_delay_input.parse().unwrap_or(0);
+        if delay != self.config.retry_base_delay_secs {
+            self.config.retry_base_delay_secs = delay;
+            changed = true;
+        }
+        self.retry_delay_input = delay.to_string();
+        
+        if self.key_binding_inputs != self.config.key_bindings {
+            self.config.key_bindings = self.key_binding_inputs.clone();
+            changed = true;
+        }
+        
+        let sponsorblock_url = self.sponsorblock_url_input.trim();
+        if sponsorblock_url != self.config.sponsorblock_api_url {
+            self.config.sponsorblock_api_url = sponsorblock_url.to_string();
+            changed = true;
+        }
+        
+        if changed {
+            if let Err(e) = self.config.save() {
+                self.error_message = Some(format!("Failed to save config: {}", e));
+            }
+        }
+    }
+    
+    // Keep the bulk filename template as typed once it's submitted or used
+    fn save_bulk_template(&mut self) {
+        if self.bulk_template_input != self.config.bulk_filename_template {
+            self.config.bulk_filename_template = self.bulk_template_input.clone();
+            if let Err(e) = self.config.save() {
+                self.error_message = Some(format!("Failed to save config: {}", e));
+            }
+        }
+    }
+    
+    // Check the managed yt-dlp binary and its rollback copy again after either changes
+    fn refresh_ytdlp_files(&mut self) {
+        self.ytdlp_installed = is_ytdlp_installed();
+        self.ytdlp_can_roll_back = ytdlp_update::previous_binary_path(&get_ytdlp_path()).exists();
+    }
+    
+    // Look ffmpeg up again after its path changes and ask it for its version
+    fn refresh_ffmpeg(&mut self) -> Task<Message> {
+        self.ffmpeg_location = find_ffmpeg(self.config.ffmpeg_path.as_deref());
+        Task::perform(
+            load_ffmpeg_versions(self.ffmpeg_location.clone()),
+            Message::FfmpegVersionsLoaded,
+        )
+    }
+    
+    // Run yt-dlp for one download, streaming its progress back as messages
+    fn start_download(&mut self, download: QueuedDownload) -> Task<Message> {
+        let Some(download_dir) = download.output_dir.clone().or_else(|| self.config.download_directory.clone()) else {
+            return Task::none();
+        };
+        
+        self.download_requests.insert(download.video_id.clone(), download.clone());
+        let QueuedDownload { video_id, filename, section, split_chapters, lyrics, format, .. } = download;
+        
+        self.downloading.insert(video_id.clone(), true);
+        self.download_errors.remove(&video_id);
+        self.download_filenames.insert(video_id.clone(), filename.clone());
+        if split_chapters {
+            self.split_chapters.insert(video_id.clone());
+        } else {
+            self.split_chapters.remove(&video_id);
+        }
+        if lyrics.is_enabled() {
+            self.lyrics_requests.insert(video_id.clone(), lyrics);
+        } else {
+            self.lyrics_requests.remove(&video_id);
+        }
+        self.download_progress.insert(video_id.clone(), DownloadProgress::default());
+        self.download_logs.insert(video_id.clone(), Vec::new());
+        self.download_messages.insert(video_id.clone(), "Starting download...".to_string());
+        self.refresh_tray();
+        
+        let vid_id = video_id.clone();
+        
+        // Use Task::run to stream progress updates!
+        Task::run(
+            download_mp3_stream_with_filename(
+                video_id,
+                download_dir,
+                filename,
+                DownloadOptions {
+                    download_section: section,
+                    split_chapters,
+                    audio_format: format,
+                    ..self.download_options()
+                },
+            ),
+            move |update| match update {
+                DownloadUpdate::Progress(progress) => {
+                    Message::DownloadProgress(vid_id.clone(), progress)
+                }
+                DownloadUpdate::Stage(stage) => {
+                    Message::DownloadStage(vid_id.clone(), stage)
+                }
+                DownloadUpdate::Log(log) => {
+                    Message::DownloadLog(vid_id.clone(), log)
+                }
+                DownloadUpdate::Completed(result) => {
+                    Message::DownloadCompleted(vid_id.clone(), result)
+                }
+            }
+        )
+    }
+    
+    // Show how many downloads are running or waiting, and their combined progress, in the tray
+    fn refresh_tray(&mut self) {
+        let active: Vec<&String> = self.downloading.iter()
+            .filter(|(_, downloading)| **downloading)
+            .map(|(video_id, _)| video_id)
+            .collect();
+        
+        let status = if active.is_empty() && self.download_queue.is_empty() {
+            "Idle".to_string()
+        } else {
+            let percents: Vec<f32> = active.iter()
+                .filter_map(|video_id| self.download_progress.get(*video_id).and_then(|p| p.percent()))
+                .collect();
+            
+            let mut status = format!("{} downloading", active.len());
+            if !percents.is_empty() {
+                let average = percents.iter().sum::<f32>() / percents.len() as f32;
+                status.push_str(&format!(" ({:.0}%)", average));
+            }
+            if !self.download_queue.is_empty() {
+                status.push_str(&format!(", {} queued", self.download_queue.len()));
+            }
+            status
+        };
+        
+        if let Some(tray) = self.tray.as_mut() {
+            tray.set_status(&status);
+        }
+    }
+    
+    // Human-readable name for a download, for notifications
+    fn download_title(&self, video_id: &str) -> String {
+        self.search_results.iter()
+            .find(|video| video.video_id == video_id)
+            .map(|video| video.title.clone())
+            .or_else(|| self.download_filenames.get(video_id).cloned())
+            .unwrap_or_else(|| video_id.to_string())
+    }
+    
+    // Keep thumbnails in memory only for the results the filter shows; the disk cache makes them cheap to reload
+    fn sync_thumbnails(&mut self) -> Task<Message> {
+        let visible: HashSet<String> = self.visible_results().iter()
+            .map(|video| video.video_id.clone())
+            .collect();
+        self.thumbnails.retain(|video_id, _| visible.contains(video_id));
+        
+        let to_load: Vec<VideoResult> = self.search_results.iter()
+            .filter(|video| visible.contains(&video.video_id))
+            .filter(|video| !self.thumbnails.contains_key(&video.video_id))
+            .filter(|video| !self.thumbnails_loading.contains(&video.video_id))
+            .cloned()
+            .collect();
+        
+        Task::batch(to_load.iter().map(|video| {
+            self.thumbnails_loading.insert(video.video_id.clone());
+            thumbnail_task(video)
+        }).collect::<Vec<_>>())
+    }
+    
+    // Search results after the filter bar, in the chosen order
+    fn visible_results(&self) -> Vec<&VideoResult> {
+        let mut results: Vec<&VideoResult> = self.search_results.iter()
+            .filter(|video| self.result_filter.matches(video))
+            .collect();
+        
+        // search_results is already in score order, and the sorts are stable
+        match self.result_filter.sort {
+            ResultSort::Score => {}
+            ResultSort::Views => results.sort_by_key(|video| std::cmp::Reverse(video.view_count().unwrap_or(0))),
+            ResultSort::Duration => results.sort_by_key(|video| video.duration_secs().unwrap_or(u64::MAX)),
+            ResultSort::Title => results.sort_by_key(|video| video.title.to_lowercase()),
+        }
+        
+        results
+    }
+    
+    // Ticked results that are still shown, in list order
+    fn checked_videos(&self) -> Vec<&VideoResult> {
+        self.visible_results()
+            .into_iter()
+            .filter(|video| self.checked_results.contains(&video.video_id))
+            .collect()
+    }
+    
+    fn filter_bar_view(&self) -> Element<'_, Message> {
+        let filter = &self.result_filter;
+        
+        row![
+            text_input("Filter title or channel", &filter.text)
+                .on_input(Message::FilterTextChanged)
+                .padding(6)
+                .size(12)
+                .width(Length::FillPortion(3)),
+            text_input("Exclude: live, cover, remix", &filter.exclude)
+                .on_input(Message::FilterExcludeChanged)
+                .padding(6)
+                .size(12)
+                .width(Length::FillPortion(3)),
+            text_input("Min views (e.g. 1M)", &filter.min_views)
+                .on_input(Message::FilterMinViewsChanged)
+                .padding(6)
+                .size(12)
+                .width(Length::FillPortion(2)),
+            text_input("Min length (3:00)", &filter.min_duration)
+                .on_input(Message::FilterMinDurationChanged)
+                .padding(6)
+                .size(12)
+                .width(Length::FillPortion(2)),
+            text_input("Max length (8:00)", &filter.max_duration)
+                .on_input(Message::FilterMaxDurationChanged)
+                .padding(6)
+                .size(12)
+                .width(Length::FillPortion(2)),
+            pick_list(&ResultSort::ALL[..], Some(filter.sort), Message::SortSelected)
+                .padding(6)
+                .text_size(12),
+            button(text("Clear").size(12))
+                .on_press_maybe(filter.is_active().then_some(Message::ClearFilters))
+                .padding(6),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+    
+    // Queue a video found from a link or search, adding it to the results list so its progress is visible
+    fn queue_found_video(&mut self, video: VideoResult, filename: String) -> Task<Message> {
+        let video_id = video.video_id.clone();
+        let mut tasks = Vec::new();
+        
+        if !self.search_results.iter().any(|v| v.video_id == video_id) {
+            tasks.push(thumbnail_task(&video));
+            self.search_results.insert(0, video);
+        }
+        
+        self.enqueue_download(QueuedDownload::new(video_id, filename));
+        tasks.push(self.start_queued_downloads());
+        Task::batch(tasks)
+    }
+    
+    // One clipboard handle, kept for the app's lifetime; on Linux copied text is only
+    // available while the handle that set it is alive
+    fn clipboard(&mut self) -> Result<&mut arboard::Clipboard, String> {
+        if self.clipboard.is_none() {
+            let clipboard = arboard::Clipboard::new()
+                .map_err(|e| format!("Clipboard unavailable: {}", e))?;
+            self.clipboard = Some(clipboard);
+        }
+        self.clipboard.as_mut().ok_or_else(|| "Clipboard unavailable".to_string())
+    }
+    
+    fn copy_to_clipboard(&mut self, text: String) -> Result<(), String> {
+        // Noted as seen so the clipboard watcher doesn't offer our own copies back
+        self.clipboard_seen = Some(text.trim().to_string());
+        self.clipboard()?
+            .set_text(text)
+            .map_err(|e| e.to_string())
+    }
+    
+    fn start_api_server(&mut self) {
+        if self.config.api_token.is_empty() {
+            match api_server::generate_token() {
+                Ok(token) => self.config.api_token = token,
+                Err(e) => {
+                    self.api_error = Some(e);
+                    return;
+                }
+            }
+            if let Err(e) = self.config.save() {
+                self.error_message = Some(format!("Failed to save config: {}", e));
+            }
+        }
+        
+        // Drop any running server first so it lets go of the port
+        self.api_server = None;
+        match ApiServer::start(self.config.api_port, self.config.api_token.clone()) {
+            Ok(server) => {
+                self.api_server = Some(server);
+                self.api_error = None;
+            }
+            Err(e) => self.api_error = Some(e),
+        }
+    }
+    
+    // Answer a request from the API, straight away or once a search finishes
+    fn handle_api_request(&mut self, request: ApiRequest) -> Task<Message> {
+        let reply = request.reply;
+        match request.command {
+            ApiCommand::Search(query) => {
+                let cookie_args = self.config.cookie_args();
+                Task::perform(
+                    async move { search_youtube(&query, &cookie_args).await },
+                    move |result| Message::ApiSearchCompleted(reply.clone(), result),
+                )
+            }
+            ApiCommand::Enqueue { input, filename } => {
+                if self.config.download_directory.is_none() {
+                    reply.send(ApiResponse::error(409, "No download directory is set in Nightingale's settings"));
+                    return Task::none();
+                }
+                
+                // A playlist queues every entry; a whole channel is too much to take from one call
+                let link = YouTubeLink::parse(&input);
+                if matches!(link, YouTubeLink::Channel { .. }) {
+                    reply.send(ApiResponse::error(400, "Channel links can't be queued; subscribe to the channel in Nightingale instead"));
+                    return Task::none();
+                }
+                let queue_all = matches!(link, YouTubeLink::Playlist { .. });
+                let cookie_args = self.config.cookie_args();
+                Task::perform(
+                    async move { search_youtube(&input, &cookie_args).await },
+                    move |result| Message::ApiEnqueueResolved(reply.clone(), filename.clone(), queue_all, result),
+                )
+            }
+            ApiCommand::QueueStatus => {
+                let title = |video_id: &str| {
+                    self.search_results.iter()
+                        .find(|v| v.video_id == video_id)
+                        .map(|v| v.title.clone())
+                        .or_else(|| self.download_filenames.get(video_id).cloned())
+                };
+                
+                let active: Vec<serde_json::Value> = self.downloading.iter()
+                    .filter(|(_, downloading)| **downloading)
+                    .map(|(video_id, _)| {
+                        let progress = self.download_progress.get(video_id);
+                        serde_json::json!({
+                            "video_id": video_id,
+                            "title": title(video_id),
+                            "percent": progress.and_then(|p| p.percent()),
+                            "status": progress.map(|p| p.status_text())
+                                .or_else(|| self.download_messages.get(video_id).cloned()),
+                        })
+                    })
+                    .collect();
+                
+                let queued: Vec<serde_json::Value> = self.download_queue.iter()
+                    .map(|download| serde_json::json!({
+                        "video_id": download.video_id,
+                        "title": title(&download.video_id),
+                        "filename": download.filename,
+                    }))
+                    .collect();
+                
+                reply.send(ApiResponse::ok(serde_json::json!({
+                    "active": active,
+                    "queued": queued,
+                })));
+                Task::none()
+            }
+            ApiCommand::History(limit) => {
+                let records = self.download_history.records();
+                let records = &records[..limit.min(records.len())];
+                reply.send(ApiResponse::ok(serde_json::json!({ "downloads": records })));
+                Task::none()
+            }
+            ApiCommand::Share(video_ids) => {
+                // Files downloaded this session, or in an earlier one if they're still there
+                let files: Vec<PathBuf> = video_ids.iter()
+                    .filter_map(|video_id| {
+                        self.downloaded_files.get(video_id).cloned().or_else(|| {
+                            self.download_history.records().iter()
+                                .find(|record| &record.video_id == video_id)
+                                .map(|record| record.file_path.clone())
+                        })
+                    })
+                    .filter(|path| path.exists())
+                    .collect();
+                
+                if files.is_empty() {
+                    reply.send(ApiResponse::error(404, "None of those videos have a downloaded file"));
+                    return Task::none();
+                }
+                
+                if let Some(server) = self.share_modal.take().and_then(|modal| modal.server) {
+                    server.stop();
+                }
+                self.open_share_modal(&files);
+                
+                reply.send(match &self.share_modal {
+                    Some(modal) => ApiResponse::ok(serde_json::json!({
+                        "url": modal.url,
+                        "files": modal.file_count,
+                    })),
+                    None => ApiResponse::error(500, "Failed to start the share server"),
+                });
+                Task::none()
+            }
+        }
+    }
+    
+    // Phone requests shown for approval count towards the share server's limit
+    fn sync_held_phone_requests(&self) {
+        if let Some(server) = self.share_modal.as_ref().and_then(|m| m.server.as_ref()) {
+            server.set_held_requests(self.phone_requests.len());
+        }
+    }
+    
+    // Start the Wi-Fi share server for one or more files and show its QR code
+    fn open_share_modal(&mut self, files: &[PathBuf]) {
+        match ShareServer::with_files(files) {
+            Ok(server) => {
+                let qr_image = server.generate_qr_code().ok();
+                let qr_handle = qr_image.as_ref()
+                    .map(|qr| image::Handle::from_rgba(qr.width, qr.height, qr.pixels.clone()));
+                let url = server.get_url().unwrap_or_else(|_| "URL unavailable".to_string());
+                
+                if let Err(e) = server.start() {
+                    eprintln!("Failed to start share server: {}", e);
+                    return;
+                }
+                
+                self.share_modal = Some(ShareModal {
+                    file_count: server.file_count(),
+                    server: Some(server),
+                    qr_image,
+                    qr_handle,
+                    qr_mode: QrMode::Url,
+                    qr_status: None,
+                    url,
+                });
+                self.sync_held_phone_requests();
+            }
+            Err(e) => {
+                eprintln!("Failed to create share server: {}", e);
+            }
+        }
+    }
+    
+    fn bulk_actions_view(&self) -> Option<Element<'_, Message>> {
+        if self.checked_results.is_empty() {
+            return self.bulk_status.as_ref().map(|status| {
+                text(status)
+                    .size(12)
+                    .style(|_theme| text::Style {
+                        color: Some(iced::Color::from_rgb(0.2, 0.6, 0.2)),
+                    })
+                    .into()
+            });
+        }
+        
+        // Bulk actions only touch the ticked results the filter still shows
+        let selected = self.checked_videos().len();
+        let hidden = self.checked_results.len().saturating_sub(selected);
+        let selected_label = if hidden > 0 {
+            format!("{} selected ({} more hidden by the filter)", selected, hidden)
+        } else {
+            format!("{} selected", selected)
+        };
+        
+        let actions = row![
+            text(selected_label).size(14),
+            button(text("Download Selected").size(12))
+                .on_press(Message::DownloadChecked)
+                .padding(6),
+            text_input("{title}", &self.bulk_template_input)
+                .on_input(Message::BulkTemplateChanged)
+                .on_submit(Message::BulkTemplateSubmitted)
+                .padding(6)
+                .size(12)
+                .width(Length::Fixed(200.0)),
+            button(text("Queue with Template").size(12))
+                .on_press(Message::QueueCheckedWithTemplate)
+                .padding(6),
+            button(text("Share Selected").size(12))
+                .on_press(Message::ShareChecked)
+                .padding(6),
+            button(text("Copy URLs").size(12))
+                .on_press(Message::CopyCheckedUrls)
+                .padding(6),
+            button(text("Clear").size(12))
+                .on_press(Message::ClearChecked)
+                .padding(6),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+        
+        let mut bar = column![
+            actions,
+            text("Template fields: {title}, {channel}, {id}, {index}. Shift-click a checkbox to select a range.")
+                .size(12)
+                .style(|_theme| text::Style {
+                    color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+                }),
+        ]
+        .spacing(6);
+        
+        if let Some(status) = &self.bulk_status {
+            bar = bar.push(text(status).size(12));
+        }
+        
+        Some(
+            container(bar)
+                .padding(10)
+                .width(Length::Fill)
+                .style(|_theme| container::Style {
+                    background: Some(iced::Background::Color(iced::Color::from_rgb(0.18, 0.18, 0.24))),
+                    border: iced::Border {
+                        color: iced::Color::from_rgb(0.4, 0.6, 0.9),
+                        width: 1.0,
+                        radius: 5.0.into(),
+                    },
+                    ..Default::default()
                 })
+                .into()
+        )
+    }
+    
+    // Whether a modal or another screen is covering the results list
+    fn is_results_covered(&self) -> bool {
+        self.show_diagnostics
+            || self.show_player_logs
+            || self.show_logs_for.is_some()
+            || self.show_settings
+            || self.show_subscriptions
+            || self.share_modal.is_some()
+            || self.trim_editor.is_some()
+            || self.rename_modal.is_some()
+    }
+    
+    // Escape closes whatever is on top: the history dropdown, the shortcuts overlay, then any modal
+    fn close_top_view(&mut self) -> Task<Message> {
+        if self.history_open {
+            self.history_open = false;
+            self.history_selection = None;
+        } else if self.show_shortcuts {
+            self.show_shortcuts = false;
+        } else if self.show_diagnostics {
+            self.show_diagnostics = false;
+        } else if self.show_player_logs {
+            self.show_player_logs = false;
+        } else if self.show_logs_for.is_some() {
+            self.show_logs_for = None;
+        } else if self.show_settings {
+            self.save_settings_inputs();
+            self.show_settings = false;
+        } else if self.show_subscriptions {
+            self.show_subscriptions = false;
+        } else if self.share_modal.is_some() {
+            return self.update(Message::CloseShare);
+        } else if self.trim_editor.is_some() {
+            return self.update(Message::CloseTrimEditor);
+        } else if self.rename_modal.is_some() {
+            self.rename_modal = None;
+        } else {
+            self.selected_video = None;
+        }
+        Task::none()
+    }
+    
+    fn selected_result(&self) -> Option<&VideoResult> {
+        let video_id = self.selected_video.as_ref()?;
+        self.visible_results().into_iter().find(|video| &video.video_id == video_id)
+    }
+    
+    // Move the keyboard selection up or down the results and keep it in view
+    fn move_selection(&mut self, delta: isize) -> Task<Message> {
+        let visible: Vec<String> = self.visible_results()
+            .iter()
+            .map(|video| video.video_id.clone())
+            .collect();
+        let count = visible.len();
+        if count == 0 {
+            return Task::none();
+        }
+        
+        let current = self.selected_video.as_ref()
+            .and_then(|id| visible.iter().position(|visible_id| visible_id == id));
+        let index = match current {
+            Some(index) => (index as isize + delta).clamp(0, count as isize - 1) as usize,
+            None if delta < 0 => count - 1,
+            None => 0,
+        };
+        self.selected_video = Some(visible[index].clone());
+        
+        // Rows vary in height, so scrolling by position in the list is approximate
+        let offset = if count > 1 { index as f32 / (count - 1) as f32 } else { 0.0 };
+        scrollable::snap_to(
+            self.results_scroll_id.clone(),
+            scrollable::RelativeOffset { x: 0.0, y: offset },
+        )
+    }
+    
+    fn shortcuts_overlay(&self) -> Element<'_, Message> {
+        let mut list = column![
+            row![
+                text("Move selection").size(14).width(Length::Fixed(220.0)),
+                text("↑ / ↓").size(14),
+            ],
+        ]
+        .spacing(8);
+        
+        for action in KeyAction::ALL {
+            list = list.push(row![
+                text(action.label()).size(14).width(Length::Fixed(220.0)),
+                text(self.config.key_bindings.get(action)).size(14),
+            ]);
+        }
+        
+        for (label, keys) in [
+            ("Focus search", if cfg!(target_os = "macos") { "Cmd+K" } else { "Ctrl+K" }),
+            ("Close dialog / leave search box", "Esc"),
+        ] {
+            list = list.push(row![
+                text(label).size(14).width(Length::Fixed(220.0)),
+                text(keys).size(14),
+            ]);
+        }
+        
+        let card = container(
+            column![
+                text("Keyboard Shortcuts").size(24),
+                list,
+                text("Change these in Settings → Keyboard Shortcuts")
+                    .size(12)
+                    .style(|_theme| text::Style {
+                        color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+                    }),
+                button(text("Close")).on_press(Message::ToggleShortcuts).padding(8),
+            ]
+            .spacing(15)
+        )
+        .padding(25)
+        .style(|_theme| container::Style {
+            background: Some(iced::Background::Color(iced::Color::from_rgb(0.15, 0.15, 0.18))),
+            border: iced::Border {
+                color: iced::Color::from_rgb(0.3, 0.3, 0.35),
+                width: 1.0,
+                radius: 8.0.into(),
+            },
+            ..Default::default()
+        });
+        
+        opaque(
+            center(card).style(|_theme| container::Style {
+                background: Some(iced::Background::Color(iced::Color::from_rgba(0.0, 0.0, 0.0, 0.6))),
+                ..Default::default()
+            })
+        )
+    }
+    
+    fn history_dropdown_view(&self) -> Option<Element<'_, Message>> {
+        if !self.history_open {
+            return None;
+        }
+        
+        let suggestions = self.search_history.suggestions(&self.search_query);
+        if suggestions.is_empty() {
+            return None;
+        }
+        
+        let mut list = column![].spacing(2);
+        for (index, entry) in suggestions.into_iter().enumerate() {
+            let selected = self.history_selection == Some(index);
+            
+            let mut label = entry.query.clone();
+            if let Some(downloaded) = &entry.downloaded {
+                label.push_str(&format!("  →  {}", downloaded.title));
             }
-        })
+            
+            let entry_button = button(text(label).size(14))
+                .on_press(Message::HistorySelected(entry.query.clone()))
+                .padding(6)
+                .width(Length::Fill)
+                .style(move |_theme, status| button::Style {
+                    background: Some(iced::Background::Color(match (selected, status) {
+                        (true, _) | (_, button::Status::Hovered) => iced::Color::from_rgb(0.25, 0.25, 0.32),
+                        _ => iced::Color::from_rgb(0.15, 0.15, 0.18),
+                    })),
+                    text_color: iced::Color::WHITE,
+                    border: iced::Border::default(),
+                    shadow: iced::Shadow::default(),
+                });
+            
+            let pin_button = button(text(if entry.pinned { "Unpin" } else { "Pin" }).size(12))
+                .on_press(Message::ToggleHistoryPin(entry.query.clone()))
+                .padding(6);
+            
+            let remove_button = button(text("✕").size(12))
+                .on_press(Message::RemoveHistoryEntry(entry.query.clone()))
+                .padding(6);
+            
+            list = list.push(
+                row![entry_button, pin_button, remove_button]
+                    .spacing(6)
+                    .align_y(iced::Alignment::Center)
+            );
+        }
+        
+        Some(
+            container(list)
+                .padding(6)
+                .width(Length::Fill)
+                .style(|_theme| container::Style {
+                    background: Some(iced::Background::Color(iced::Color::from_rgb(0.12, 0.12, 0.15))),
+                    border: iced::Border {
+                        color: iced::Color::from_rgb(0.3, 0.3, 0.35),
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into()
+        )
+    }
+    
+    // Pinned searches, one click away
+    fn saved_searches_view(&self) -> Option<Element<'_, Message>> {
+        let mut saved = row![text("Saved:").size(14)]
+            .spacing(8)
+            .align_y(iced::Alignment::Center);
+        
+        let mut any = false;
+        for entry in self.search_history.pinned() {
+            any = true;
+            saved = saved.push(
+                button(text(&entry.query).size(12))
+                    .on_press(Message::HistorySelected(entry.query.clone()))
+                    .padding(6)
+            );
+        }
+        
+        any.then(|| saved.into())
+    }
+    
+    fn phone_requests_view(&self) -> Option<Element<'_, Message>> {
+        if self.phone_requests.is_empty() {
+            return None;
+        }
+        
+        let mut requests = column![
+            text("📱 Requests from phone")
+                .size(16)
+                .style(|_theme| text::Style {
+                    color: Some(iced::Color::from_rgb(0.4, 0.6, 0.9)),
+                }),
+        ]
+        .spacing(8);
+        
+        for (index, input) in self.phone_requests.iter().enumerate() {
+            requests = requests.push(
+                row![
+                    text(input).size(14).width(Length::Fill),
+                    button(text("Download").size(12))
+                        .on_press(Message::ApprovePhoneRequest(index))
+                        .padding(6),
+                    button(text("Dismiss").size(12))
+                        .on_press(Message::DismissPhoneRequest(index))
+                        .padding(6),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+        
+        Some(
+            container(requests)
+                .padding(15)
+                .width(Length::Fill)
+                .style(|_theme| container::Style {
+                    background: Some(iced::Background::Color(iced::Color::from_rgb(0.15, 0.15, 0.18))),
+                    border: iced::Border {
+                        color: iced::Color::from_rgb(0.4, 0.6, 0.9),
+                        width: 1.0,
+                        radius: 5.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into(),
+        )
     }
 
+    fn playlist_choice_view(&self) -> Option<Element<'_, Message>> {
+        self.playlist_choice.as_ref()?;
+        
+        let choice = row![
+            text("This link is to a video in a playlist.").size(14).width(Length::Fill),
+            button(text("Just this video").size(12))
+                .on_press(Message::SearchPlaylistLink(false))
+                .padding(6),
+            button(text("Whole playlist").size(12))
+                .on_press(Message::SearchPlaylistLink(true))
+                .padding(6),
+            button(text("✕").size(12))
+                .on_press(Message::DismissPlaylistChoice)
+                .padding(6),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+        
+        Some(
+            container(choice)
+                .padding(8)
+                .width(Length::Fill)
+                .style(|_theme| container::Style {
+                    background: Some(iced::Background::Color(iced::Color::from_rgb(0.15, 0.15, 0.18))),
+                    border: iced::Border {
+                        color: iced::Color::from_rgb(0.4, 0.6, 0.9),
+                        width: 1.0,
+                        radius: 5.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into(),
+        )
+    }
+    
+    fn clipboard_link_view(&self) -> Option<Element<'_, Message>> {
+        let link = self.clipboard_link.as_ref()?;
+        
+        let prompt = row![
+            text("📋 Copied link:").size(14),
+            text(link).size(14).width(Length::Fill),
+            button(text("Search").size(12))
+                .on_press(Message::SearchClipboardLink)
+                .padding(6),
+            button(text("Download").size(12))
+                .on_press(Message::DownloadClipboardLink)
+                .padding(6),
+            button(text("✕").size(12))
+                .on_press(Message::DismissClipboardLink)
+                .padding(6),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+        
+        Some(
+            container(prompt)
+                .padding(8)
+                .width(Length::Fill)
+                .style(|_theme| container::Style {
+                    background: Some(iced::Background::Color(iced::Color::from_rgb(0.15, 0.15, 0.18))),
+                    border: iced::Border {
+                        color: iced::Color::from_rgb(0.3, 0.3, 0.35),
+                        width: 1.0,
+                        radius: 5.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into(),
+        )
+    }
+    
     fn view(&self) -> Element<'_, Message> {
+        if self.show_diagnostics {
+            return self.diagnostics_view();
+        }
+        
         if self.show_player_logs {
             return self.player_logs_view();
         }
@@ -1171,10 +4806,18 @@ impl Songbird {
             return self.settings_view();
         }
         
+        if self.show_subscriptions {
+            return self.subscriptions_view();
+        }
+        
         if let Some(modal) = &self.share_modal {
             return self.share_modal_view(modal);
         }
         
+        if let Some(editor) = &self.trim_editor {
+            return self.trim_editor_view(editor);
+        }
+        
         if let Some(modal) = &self.rename_modal {
             return self.rename_modal_view(modal);
         }
@@ -1187,7 +4830,19 @@ impl Songbird {
             .on_press(Message::ToggleSettings)
             .padding(8);
         
-        let title_row = row![title, settings_button]
+        let new_items: usize = self.subscription_new.values().map(|items| items.len()).sum();
+        let subscriptions_button = button(
+            text(if new_items > 0 {
+                format!("🔔 {} new", new_items)
+            } else {
+                "🔔".to_string()
+            })
+            .size(if new_items > 0 { 16 } else { 24 })
+        )
+            .on_press(Message::ToggleSubscriptions)
+            .padding(8);
+        
+        let title_row = row![title, subscriptions_button, settings_button]
             .spacing(10)
             .width(Length::Fill);
 
@@ -1219,6 +4874,24 @@ impl Songbird {
             .width(Length::Fill);
 
         let mut header = column![title_row, search_row].spacing(20);
+        
+        if let Some(dropdown) = self.history_dropdown_view() {
+            header = header.push(dropdown);
+        } else if let Some(saved) = self.saved_searches_view() {
+            header = header.push(saved);
+        }
+        
+        if let Some(requests) = self.phone_requests_view() {
+            header = header.push(requests);
+        }
+        
+        if let Some(choice) = self.playlist_choice_view() {
+            header = header.push(choice);
+        }
+        
+        if let Some(prompt) = self.clipboard_link_view() {
+            header = header.push(prompt);
+        }
 
         // Show error message if any
         if let Some(error) = &self.error_message {
@@ -1233,13 +4906,28 @@ impl Songbird {
 
         // Show results
         let content = if !self.search_results.is_empty() {
-            let results_title = text(format!("Results ({})", self.search_results.len()))
-                .size(20)
-                .width(Length::Fill);
+            let visible_results = self.visible_results();
+            let count_label = if visible_results.len() == self.search_results.len() {
+                format!("Results ({})", self.search_results.len())
+            } else {
+                format!("Results ({} of {})", visible_results.len(), self.search_results.len())
+            };
+            
+            let results_title = row![
+                text(count_label)
+                    .size(20)
+                    .width(Length::Fill),
+                text(format!("Press {} for keyboard shortcuts", self.config.key_bindings.show_shortcuts))
+                    .size(12)
+                    .style(|_theme| text::Style {
+                        color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+                    }),
+            ]
+            .align_y(iced::Alignment::Center);
 
             let mut results_list = column![].spacing(10);
 
-            for video in &self.search_results {
+            for video in visible_results {
                 let video_title = text(&video.title)
                     .size(16)
                     .width(Length::Fill);
@@ -1283,13 +4971,20 @@ impl Songbird {
                 });
 
                 let is_downloading = self.downloading.get(&video.video_id).copied().unwrap_or(false);
+                let is_queued = self.download_queue.iter().any(|d| d.video_id == video.video_id);
                 let download_status = self.download_messages.get(&video.video_id);
                 
                 let download_button = button(
-                    text(if is_downloading { "Downloading..." } else { "Download MP3" })
-                        .size(14)
+                    text(if is_downloading {
+                        "Downloading..."
+                    } else if is_queued {
+                        "Queued"
+                    } else {
+                        "Download MP3"
+                    })
+                    .size(14)
                 )
-                .on_press_maybe(if is_downloading {
+                .on_press_maybe(if is_downloading || is_queued {
                     None
                 } else {
                     Some(Message::DownloadMp3(video.video_id.clone()))
@@ -1332,6 +5027,11 @@ impl Songbird {
                     None
                 };
                 
+                let chosen_last_time = self.searched_query.as_deref()
+                    .and_then(|query| self.search_history.get(query))
+                    .and_then(|entry| entry.downloaded.as_ref())
+                    .is_some_and(|downloaded| downloaded.video_id == video.video_id);
+                
                 let mut info_column = column![
                     video_title,
                     video_channel,
@@ -1343,23 +5043,95 @@ impl Songbird {
                 .spacing(5)
                 .width(Length::Fill);
                 
+                if chosen_last_time {
+                    info_column = info_column.push(
+                        text("★ Downloaded last time you searched for this")
+                            .size(12)
+                            .style(|_theme| text::Style {
+                                color: Some(iced::Color::from_rgb(0.9, 0.7, 0.2)),
+                            })
+                    );
+                }
+                
+                // Long videos are the ones likely to be albums or mixes with chapters
+                if video.duration_secs().is_none_or(|secs| secs >= 10 * 60) {
+                    let expanded = self.expanded_chapters.contains(&video.video_id);
+                    info_column = info_column.push(
+                        button(text(if expanded { "▾ Chapters" } else { "▸ Chapters" }).size(12))
+                            .on_press(Message::ToggleChapters(video.video_id.clone()))
+                            .padding(6)
+                    );
+                    
+                    if expanded {
+                        if let Some(status) = self.chapters_status.get(&video.video_id) {
+                            info_column = info_column.push(
+                                text(status)
+                                    .size(12)
+                                    .style(|_theme| text::Style {
+                                        color: Some(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+                                    })
+                            );
+                        }
+                        if let Some(chapters) = self.video_chapters.get(&video.video_id) {
+                            let mut chapter_list = column![].spacing(2);
+                            for (index, chapter) in chapters.iter().enumerate() {
+                                chapter_list = chapter_list.push(
+                                    text(format!(
+                                        "{:>2}. {}  {}",
+                                        index + 1,
+                                        trim::format_time(chapter.start_time),
+                                        chapter.title,
+                                    ))
+                                    .size(12)
+                                );
+                            }
+                            info_column = info_column.push(chapter_list);
+                        }
+                    }
+                }
+                
                 if let Some(logs_btn) = view_logs_button {
                     info_column = info_column.push(logs_btn);
                 }
                 
                 if let Some(share_btn) = share_button {
-                    info_column = info_column.push(share_btn);
+                    let play_button = button(text("▶ Play").size(12))
+                        .on_press(Message::PlayFile(video.video_id.clone()))
+                        .padding(6);
+                    
+                    let trim_button = button(text("✂ Trim").size(12))
+                        .on_press(Message::ShowTrimEditor(video.video_id.clone()))
+                        .padding(6);
+                    
+                    info_column = info_column.push(row![share_btn, play_button, trim_button].spacing(10));
                 }
                 
                 // Show downloading indicator
                 if is_downloading {
+                    let progress = self.download_progress.get(&video.video_id);
+                    let status = progress
+                        .map(|p| p.status_text())
+                        .unwrap_or_else(|| "Downloading...".to_string());
+                    
                     info_column = info_column.push(
-                        text("⏳ Downloading...")
+                        text(format!("⏳ {}", status))
                             .size(14)
                             .style(|_theme| text::Style {
                                 color: Some(iced::Color::from_rgb(0.4, 0.6, 0.9)),
                             })
                     );
+                    
+                    // Only show the bar while the size is known; conversion has no progress to report
+                    if let Some(percent) = progress
+                        .filter(|p| p.stage == DownloadStage::Downloading)
+                        .and_then(|p| p.percent())
+                    {
+                        info_column = info_column.push(
+                            progress_bar(0.0..=100.0, percent)
+                                .height(6)
+                                .width(Length::Fixed(300.0))
+                        );
+                    }
                 }
                 
                 if let Some(status) = download_status {
@@ -1376,11 +5148,24 @@ impl Songbird {
                     );
                 }
                 
+                if let Some(action) = self.download_errors.get(&video.video_id).and_then(|e| e.suggested_action()) {
+                    info_column = info_column.push(
+                        button(text(action.label()).size(12))
+                            .on_press(Message::RunSuggestedAction(video.video_id.clone(), action))
+                            .padding(6)
+                    );
+                }
+                
                 let video_info = info_column;
 
+                let check_id = video.video_id.clone();
+                let select_box = checkbox("", self.checked_results.contains(&video.video_id))
+                    .on_toggle(move |checked| Message::ResultChecked(check_id.clone(), checked));
+                
                 let content_row = if let Some(thumbnail_handle) = self.thumbnails.get(&video.video_id) {
                     // Show thumbnail with video info
                     row![
+                        select_box,
                         Image::new(thumbnail_handle.clone())
                             .width(120)
                             .height(90),
@@ -1390,6 +5175,7 @@ impl Songbird {
                 } else {
                     // Show just video info while thumbnail loads
                     row![
+                        select_box,
                         container(text("Loading...").size(10))
                             .width(120)
                             .height(90)
@@ -1404,16 +5190,21 @@ impl Songbird {
                     .spacing(15)
                 };
 
+                let selected = self.selected_video.as_ref() == Some(&video.video_id);
                 let video_container = container(content_row)
                     .padding(15)
                     .width(Length::Fill)
-                    .style(|_theme| container::Style {
+                    .style(move |_theme| container::Style {
                         background: Some(iced::Background::Color(iced::Color::from_rgb(
                             0.15, 0.15, 0.18,
                         ))),
                         border: iced::Border {
-                            color: iced::Color::from_rgb(0.25, 0.25, 0.3),
-                            width: 1.0,
+                            color: if selected {
+                                iced::Color::from_rgb(0.4, 0.6, 0.9)
+                            } else {
+                                iced::Color::from_rgb(0.25, 0.25, 0.3)
+                            },
+                            width: if selected { 2.0 } else { 1.0 },
                             radius: 5.0.into(),
                         },
                         ..Default::default()
@@ -1427,11 +5218,12 @@ impl Songbird {
                 .width(Length::Fill)
                 .id(self.results_scroll_id.clone());
 
-            column![
-                header,
-                results_title,
-                scrollable_results,
-            ]
+            let mut results_column = column![header, results_title, self.filter_bar_view()].spacing(20);
+            if let Some(bulk_bar) = self.bulk_actions_view() {
+                results_column = results_column.push(bulk_bar);
+            }
+            
+            results_column.push(scrollable_results)
             .spacing(20)
             .padding(20)
         } else if self.is_searching {
@@ -1463,7 +5255,192 @@ impl Songbird {
                 .padding(20)
         };
 
-        container(content)
+        let main_view = container(content)
+            .width(Length::Fill)
+            .height(Length::Fill);
+        
+        if self.show_shortcuts {
+            stack![main_view, self.shortcuts_overlay()].into()
+        } else {
+            main_view.into()
+        }
+    }
+    
+    fn subscriptions_view(&self) -> Element<'_, Message> {
+        let title = text("Subscriptions")
+            .size(32);
+        
+        let back_button = button(text("← Back"))
+            .on_press(Message::ToggleSubscriptions)
+            .padding(10);
+        
+        let header = row![back_button, title]
+            .spacing(20)
+            .width(Length::Fill);
+        
+        let grey = |_theme: &iced::Theme| text::Style {
+            color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+        };
+        
+        let info = text("Playlists and channels are re-listed periodically. New uploads are queued automatically, or wait here for approval.")
+            .size(14)
+            .style(grey);
+        
+        let add_row = row![
+            text_input("Playlist or channel URL", &self.subscription_url_input)
+                .on_input(Message::SubscriptionUrlChanged)
+                .on_submit(Message::AddSubscription)
+                .padding(8)
+                .width(Length::Fill),
+            button(text("Subscribe"))
+                .on_press(Message::AddSubscription)
+                .padding(8),
+        ]
+        .spacing(10);
+        
+        let interval_row = row![
+            text("Check every").size(14),
+            text_input("60", &self.subscription_interval_input)
+                .on_input(Message::SubscriptionIntervalChanged)
+                .on_submit(Message::SubscriptionIntervalSubmitted)
+                .padding(6)
+                .size(14)
+                .width(Length::Fixed(60.0)),
+            text("minutes").size(14),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+        
+        let mut content = column![header, info, add_row].spacing(15);
+        
+        if let Some(error) = &self.subscription_error {
+            content = content.push(
+                text(error.clone())
+                    .size(14)
+                    .style(|_theme| text::Style {
+                        color: Some(iced::Color::from_rgb(0.9, 0.3, 0.3)),
+                    })
+            );
+        }
+        content = content.push(interval_row);
+        
+        if self.config.channel_subscriptions.is_empty() {
+            content = content.push(text("No subscriptions yet").size(14).style(grey));
+        }
+        
+        for subscription in &self.config.channel_subscriptions {
+            let url = subscription.url.clone();
+            let checking = self.subscriptions_checking.contains(&url);
+            
+            let folder = subscription.output_dir
+                .as_ref()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_else(|| "Default download folder".to_string());
+            
+            let mut card = column![
+                row![
+                    text(&subscription.name).size(18).width(Length::Fill),
+                    button(text(if checking { "Checking..." } else { "Check Now" }).size(12))
+                        .on_press_maybe((!checking).then(|| Message::CheckSubscriptionNow(url.clone())))
+                        .padding(6),
+                    button(text("Remove").size(12))
+                        .on_press(Message::RemoveSubscription(url.clone()))
+                        .padding(6),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+                text(&subscription.url).size(12).style(grey),
+                row![
+                    text("Format:").size(14),
+                    pick_list(AudioFormat::ALL, Some(subscription.format), {
+                        let url = url.clone();
+                        move |format| Message::SubscriptionFormatSelected(url.clone(), format)
+                    }),
+                    text(folder).size(14),
+                    button(text("Change Folder").size(12))
+                        .on_press(Message::PickSubscriptionFolder(url.clone()))
+                        .padding(6),
+                    button(text("Use Default").size(12))
+                        .on_press_maybe(subscription.output_dir.is_some().then(|| Message::ClearSubscriptionFolder(url.clone())))
+                        .padding(6),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+                checkbox("Download new items automatically", subscription.auto_download)
+                    .on_toggle({
+                        let url = url.clone();
+                        move |enabled| Message::SubscriptionAutoToggled(url.clone(), enabled)
+                    }),
+            ]
+            .spacing(8);
+            
+            if let Some(status) = self.subscription_status.get(&url) {
+                card = card.push(text(status).size(12).style(grey));
+            }
+            
+            if let Some(pending) = self.subscription_new.get(&url) {
+                card = card.push(
+                    row![
+                        text(format!("{} new item(s)", pending.len())).size(14),
+                        button(text("Download All").size(12))
+                            .on_press(Message::ApproveAllSubscriptionItems(url.clone()))
+                            .padding(6),
+                        button(text("Dismiss All").size(12))
+                            .on_press(Message::DismissAllSubscriptionItems(url.clone()))
+                            .padding(6),
+                    ]
+                    .spacing(10)
+                    .align_y(iced::Alignment::Center)
+                );
+                for video in pending {
+                    card = card.push(
+                        row![
+                            text(&video.title).size(14).width(Length::Fill),
+                            text(&video.duration).size(12).style(grey),
+                            button(text("Download").size(12))
+                                .on_press(Message::ApproveSubscriptionItem(url.clone(), video.video_id.clone()))
+                                .padding(6),
+                            button(text("Dismiss").size(12))
+                                .on_press(Message::DismissSubscriptionItem(url.clone(), video.video_id.clone()))
+                                .padding(6),
+                        ]
+                        .spacing(10)
+                        .align_y(iced::Alignment::Center)
+                    );
+                }
+            }
+            
+            content = content.push(
+                container(card)
+                    .padding(15)
+                    .width(Length::Fill)
+                    .style(|_theme| container::Style {
+                        background: Some(iced::Background::Color(iced::Color::from_rgb(0.15, 0.15, 0.2))),
+                        border: iced::Border {
+                            radius: 6.0.into(),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+            );
+        }
+        
+        if !self.subscription_downloads.is_empty() {
+            content = content.push(text("Recent Downloads:").size(18));
+            for (video_id, title) in &self.subscription_downloads {
+                let status = self.download_messages.get(video_id).cloned().unwrap_or_default();
+                content = content.push(
+                    row![
+                        text(title).size(14).width(Length::FillPortion(2)),
+                        text(status).size(12).style(grey).width(Length::FillPortion(3)),
+                    ]
+                    .spacing(10)
+                );
+            }
+        }
+        
+        container(scrollable(content.padding(20)))
+            .padding(20)
             .width(Length::Fill)
             .height(Length::Fill)
             .into()
@@ -1498,50 +5475,425 @@ impl Songbird {
                 })
         };
         
-        let change_button = button(text("Choose Directory"))
-            .on_press(Message::PickDirectory)
-            .padding(10);
+        let change_button = button(text("Choose Directory"))
+            .on_press(Message::PickDirectory)
+            .padding(10);
+        
+        // yt-dlp section
+        let ytdlp_label = text("yt-dlp Binary:")
+            .size(18);
+        
+        let ytdlp_path = get_ytdlp_path();
+        let ytdlp_path_display = text(format!("Path: {}", ytdlp_path.display()))
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            });
+        
+        let ytdlp_status_display = text(&self.ytdlp_status)
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(if self.ytdlp_status.contains("failed") || self.ytdlp_status.contains("not found") {
+                    iced::Color::from_rgb(0.8, 0.2, 0.2)
+                } else {
+                    iced::Color::from_rgb(0.2, 0.6, 0.2)
+                }),
+            });
+        
+        let ytdlp_version_display = text(format!(
+            "Version: {}",
+            self.ytdlp_version.as_deref().unwrap_or("unknown")
+        ))
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            });
+        
+        let channel_picker = row![
+            text("Channel:").size(14),
+            pick_list(
+                UpdateChannel::ALL,
+                Some(self.config.ytdlp_channel),
+                Message::YtDlpChannelSelected,
+            )
+            .padding(6),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+        
+        let installed = self.ytdlp_installed;
+        let update_available = installed
+            && self.ytdlp_latest.is_some()
+            && self.ytdlp_latest != self.ytdlp_version;
+        
+        let install_button = button(text(if self.ytdlp_installing {
+            "Installing..."
+        } else if update_available {
+            "Update yt-dlp"
+        } else {
+            "Install yt-dlp"
+        }))
+            .on_press_maybe(if self.ytdlp_installing || (installed && !update_available) {
+                None
+            } else {
+                Some(Message::InstallYtDlp)
+            })
+            .padding(10);
+        
+        let check_button = button(text(if self.ytdlp_checking { "Checking..." } else { "Check for Updates" }))
+            .on_press_maybe(if self.ytdlp_checking || self.ytdlp_installing {
+                None
+            } else {
+                Some(Message::CheckYtDlpUpdate)
+            })
+            .padding(10);
+        
+        let rollback_button = button(text("Roll Back"))
+            .on_press_maybe(if !self.ytdlp_installing && self.ytdlp_can_roll_back {
+                Some(Message::RollbackYtDlp)
+            } else {
+                None
+            })
+            .padding(10);
+        
+        // ffmpeg section
+        let ffmpeg_label = text("ffmpeg:")
+            .size(18);
+        
+        // find_ffmpeg quietly falls back to auto-detection when the custom path is gone
+        let ffmpeg_path_display = text(match (&self.ffmpeg_location, &self.config.ffmpeg_path) {
+            (Some(location), Some(custom)) if *location == custom.to_string_lossy() => {
+                format!("Path: {} (custom)", location)
+            }
+            (Some(location), Some(custom)) => {
+                format!("Custom path {} not found - using {}", custom.display(), location)
+            }
+            (Some(location), None) => format!("Path: {}", location),
+            (None, Some(custom)) => {
+                format!("Custom path {} not found - MP3 conversion will fail", custom.display())
+            }
+            (None, None) => "ffmpeg not found - MP3 conversion will fail".to_string(),
+        })
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            });
+        
+        let ffmpeg_versions_display = text(format!(
+            "ffmpeg: {} · ffprobe: {}",
+            self.ffmpeg_versions.ffmpeg.as_deref().unwrap_or("not found"),
+            self.ffmpeg_versions.ffprobe.as_deref().unwrap_or("not found"),
+        ))
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            });
+        
+        let ffmpeg_status_display = text(&self.ffmpeg_status)
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(if self.ffmpeg_status.contains("failed") {
+                    iced::Color::from_rgb(0.8, 0.2, 0.2)
+                } else {
+                    iced::Color::from_rgb(0.2, 0.6, 0.2)
+                }),
+            });
+        
+        let install_ffmpeg_button = button(text(if self.ffmpeg_installing { "Installing..." } else { "Install ffmpeg" }))
+            .on_press_maybe(if self.ffmpeg_installing {
+                None
+            } else {
+                Some(Message::InstallFfmpeg)
+            })
+            .padding(10);
+        
+        let choose_ffmpeg_button = button(text("Choose ffmpeg..."))
+            .on_press(Message::PickFfmpeg)
+            .padding(10);
+        
+        let clear_ffmpeg_button = button(text("Auto-detect"))
+            .on_press_maybe(self.config.ffmpeg_path.as_ref().map(|_| Message::ClearFfmpegPath))
+            .padding(10);
+        
+        // Cookies section
+        let cookies_label = text("Cookies:")
+            .size(18);
+        
+        let cookies_info = text("Used for age-restricted and members-only videos. A cookies.txt file takes precedence over the browser.")
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            });
+        
+        let browser_options: Vec<String> = std::iter::once(NO_COOKIE_BROWSER)
+            .chain(COOKIE_BROWSERS.iter().copied())
+            .map(|b| b.to_string())
+            .collect();
+        
+        let browser_picker = row![
+            text("Browser:").size(14),
+            pick_list(
+                browser_options,
+                // A browser this platform can't read from is ignored, so show it as none
+                Some(self.config.browser_for_cookies.clone()
+                    .filter(|browser| COOKIE_BROWSERS.contains(&browser.as_str()))
+                    .unwrap_or_else(|| NO_COOKIE_BROWSER.to_string())),
+                Message::CookieBrowserSelected,
+            )
+            .padding(6),
+            text_input("Profile (optional)", &self.cookie_profile_input)
+                .on_input(Message::CookieProfileChanged)
+                .on_submit(Message::SettingsInputSubmitted)
+                .padding(6)
+                .size(14)
+                .width(Length::Fixed(200.0)),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+        
+        let cookies_file_display = text(match &self.config.cookies_file {
+            Some(file) => format!("cookies.txt: {}", file.display()),
+            None => "cookies.txt: not set".to_string(),
+        })
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            });
+        
+        let choose_cookies_button = button(text("Choose cookies.txt..."))
+            .on_press(Message::PickCookiesFile)
+            .padding(10);
+        
+        let clear_cookies_button = button(text("Clear"))
+            .on_press_maybe(self.config.cookies_file.as_ref().map(|_| Message::ClearCookiesFile))
+            .padding(10);
+        
+        // Retry section
+        let retries_label = text("Download Retries:")
+            .size(18);
+        
+        let retries_info = text("Failed downloads are retried with each player client and format in turn, waiting longer between each attempt.")
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            });
+        
+        let mut retries_list = column![].spacing(8);
+        for (index, strategy) in self.retry_strategy_inputs.iter().enumerate() {
+            retries_list = retries_list.push(
+                row![
+                    text(format!("{}.", index + 1)).size(14),
+                    text_input("Player client", &strategy.player_client)
+                        .on_input(move |client| Message::RetryClientChanged(index, client))
+                        .on_submit(Message::SettingsInputSubmitted)
+                        .padding(6)
+                        .size(14)
+                        .width(Length::Fixed(160.0)),
+                    text_input("Format (default)", strategy.format.as_deref().unwrap_or(""))
+                        .on_input(move |format| Message::RetryFormatChanged(index, format))
+                        .on_submit(Message::SettingsInputSubmitted)
+                        .padding(6)
+                        .size(14)
+                        .width(Length::Fixed(240.0)),
+                    button(text("Remove").size(12))
+                        .on_press(Message::RemoveRetryStrategy(index))
+                        .padding(6),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+        
+        let retry_controls = row![
+            button(text("Add Attempt"))
+                .on_press(Message::AddRetryStrategy)
+                .padding(10),
+            text("First retry after (seconds):").size(14),
+            text_input("5", &self.retry_delay_input)
+                .on_input(Message::RetryDelayChanged)
+                .on_submit(Message::SettingsInputSubmitted)
+                .padding(6)
+                .size(14)
+                .width(Length::Fixed(60.0)),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+        
+        let player_logs_label = text("Player Logs:")
+            .size(18);
+        
+        let player_logs_count = text(format!("{} log entries", self.player_logs.len()))
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            });
+        
+        let view_logs_button = button(text("View Player Logs"))
+            .on_press(Message::ShowPlayerLogs)
+            .padding(10);
+        
+        let background_label = text("Background:")
+            .size(18);
+        
+        let notifications_toggle = checkbox("Notify when a download finishes or fails", self.config.notifications_enabled)
+            .on_toggle(Message::NotificationsToggled);
+        
+        let clipboard_toggle = checkbox("Offer to search or download YouTube links when they're copied", self.config.clipboard_watch)
+            .on_toggle(Message::ClipboardWatchToggled);
+        
+        let tray_toggle = checkbox("Keep running in the tray when the window is closed", self.config.minimize_to_tray)
+            .on_toggle_maybe(self.tray.as_ref().map(|_| Message::MinimizeToTrayToggled as fn(bool) -> Message));
+        
+        let tray_status_display = text(match &self.tray_error {
+            Some(e) => format!("Tray unavailable: {}", e),
+            None => "Tray icon shows overall download progress".to_string(),
+        })
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            });
+        
+        let sponsorblock_label = text("Segment Removal:")
+            .size(18);
+        
+        let sponsorblock_info = text("Cut sections marked in the SponsorBlock database out of music videos")
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            });
+        
+        let sponsorblock_toggle = checkbox("Remove marked segments when downloading", self.config.sponsorblock_enabled)
+            .on_toggle(Message::SponsorBlockToggled);
+        
+        let mut sponsorblock_categories = row![].spacing(15);
+        for (category, label) in sponsorblock::CATEGORIES {
+            let enabled = self.config.sponsorblock_categories.iter().any(|c| c == category);
+            sponsorblock_categories = sponsorblock_categories.push(
+                checkbox(*label, enabled)
+                    .on_toggle(move |enabled| Message::SponsorBlockCategoryToggled(category, enabled))
+            );
+        }
+        
+        let sponsorblock_url_input = row![
+            text("Server:").size(14),
+            text_input(sponsorblock::DEFAULT_API_URL, &self.sponsorblock_url_input)
+                .on_input(Message::SponsorBlockUrlChanged)
+                .on_submit(Message::SettingsInputSubmitted)
+                .padding(6)
+                .size(14)
+                .width(Length::Fixed(320.0)),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+        
+        let loudness_label = text("Loudness:")
+            .size(18);
+        
+        let loudness_info = text("Measure each download (EBU R128) and bring it to a common volume, or tag it with ReplayGain for players that support it")
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            });
         
-        // yt-dlp section
-        let ytdlp_label = text("yt-dlp Binary:")
+        let loudness_controls = row![
+            pick_list(
+                LoudnessMode::ALL,
+                Some(self.config.loudness_mode),
+                Message::LoudnessModeSelected,
+            ),
+            text("Target: -").size(14),
+            text_input("14", &self.loudness_target_input)
+                .on_input(Message::LoudnessTargetChanged)
+                .on_submit(Message::LoudnessTargetSubmitted)
+                .padding(6)
+                .size(14)
+                .width(Length::Fixed(60.0)),
+            text("LUFS (-14 suits streaming, -23 broadcast)").size(14),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+        
+        let api_label = text("Local API:")
             .size(18);
         
-        let ytdlp_path = get_ytdlp_path();
-        let ytdlp_path_display = text(format!("Path: {}", ytdlp_path.display()))
+        let api_info = text("Let scripts, bookmarklets and other tools on this computer search, queue downloads and share files over HTTP (see the README for the endpoints)")
             .size(14)
             .style(|_theme| text::Style {
                 color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
             });
         
-        let ytdlp_status_display = text(&self.ytdlp_status)
+        let api_status = match (&self.api_server, &self.api_error) {
+            (_, Some(error)) => error.clone(),
+            (Some(_), None) => format!("Listening on http://127.0.0.1:{}", self.config.api_port),
+            (None, None) => "Off".to_string(),
+        };
+        
+        // The port can only change while the server is stopped
+        let api_controls = row![
+            checkbox("Enable on port", self.config.api_enabled)
+                .on_toggle(Message::ApiToggled),
+            text_input("8765", &self.config.api_port.to_string())
+                .on_input_maybe((!self.config.api_enabled).then_some(Message::ApiPortChanged))
+                .padding(6)
+                .size(14)
+                .width(Length::Fixed(80.0)),
+            text(api_status).size(14),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+        
+        let api_token = row![
+            text("Token:").size(14),
+            text(if self.config.api_token.is_empty() { "Generated when the API is enabled" } else { &self.config.api_token })
+                .size(14)
+                .font(iced::Font::MONOSPACE),
+            button(text("Copy").size(12))
+                .on_press_maybe((!self.config.api_token.is_empty()).then_some(Message::CopyApiToken))
+                .padding(6),
+            button(text("Regenerate").size(12))
+                .on_press(Message::RegenerateApiToken)
+                .padding(6),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+        
+        let shortcuts_label = text("Keyboard Shortcuts:")
+            .size(18);
+        
+        let shortcuts_info = text("A single character (j, ?) or a key name (Enter, Space, Tab). Arrow keys always move the selection.")
             .size(14)
             .style(|_theme| text::Style {
-                color: Some(if self.ytdlp_status.contains("failed") || self.ytdlp_status.contains("not found") {
-                    iced::Color::from_rgb(0.8, 0.2, 0.2)
-                } else {
-                    iced::Color::from_rgb(0.2, 0.6, 0.2)
-                }),
+                color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
             });
         
-        let install_button = button(text(if self.ytdlp_installing { "Installing..." } else { "Install yt-dlp" }))
-            .on_press_maybe(if self.ytdlp_installing || is_ytdlp_installed() {
-                None
-            } else {
-                Some(Message::InstallYtDlp)
-            })
-            .padding(10);
+        let mut shortcuts_list = column![].spacing(8);
+        for action in KeyAction::ALL {
+            shortcuts_list = shortcuts_list.push(
+                row![
+                    text(action.label()).size(14).width(Length::Fixed(200.0)),
+                    text_input("Unbound", self.key_binding_inputs.get(action))
+                        .on_input(move |key| Message::KeyBindingChanged(action, key))
+                        .on_submit(Message::SettingsInputSubmitted)
+                        .padding(6)
+                        .size(14)
+                        .width(Length::Fixed(120.0)),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+            );
+        }
         
-        let player_logs_label = text("Player Logs:")
+        let diagnostics_label = text("Diagnostics:")
             .size(18);
         
-        let player_logs_count = text(format!("{} log entries", self.player_logs.len()))
+        let diagnostics_info = text("Check yt-dlp, ffmpeg, the download directory and network access")
             .size(14)
             .style(|_theme| text::Style {
                 color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
             });
         
-        let view_logs_button = button(text("View Player Logs"))
-            .on_press(Message::ShowPlayerLogs)
+        let diagnostics_button = button(text("Run Diagnostics"))
+            .on_press(Message::ShowDiagnostics)
             .padding(10);
         
         let settings_content = column![
@@ -1556,8 +5908,36 @@ impl Songbird {
             column![
                 ytdlp_label,
                 ytdlp_path_display,
+                ytdlp_version_display,
+                channel_picker,
                 ytdlp_status_display,
-                install_button,
+                row![install_button, check_button, rollback_button].spacing(10),
+            ]
+            .spacing(10)
+            .padding(20),
+            column![
+                ffmpeg_label,
+                ffmpeg_path_display,
+                ffmpeg_versions_display,
+                ffmpeg_status_display,
+                row![install_ffmpeg_button, choose_ffmpeg_button, clear_ffmpeg_button].spacing(10),
+            ]
+            .spacing(10)
+            .padding(20),
+            column![
+                cookies_label,
+                cookies_info,
+                browser_picker,
+                cookies_file_display,
+                row![choose_cookies_button, clear_cookies_button].spacing(10),
+            ]
+            .spacing(10)
+            .padding(20),
+            column![
+                retries_label,
+                retries_info,
+                retries_list,
+                retry_controls,
             ]
             .spacing(10)
             .padding(20),
@@ -1568,6 +5948,53 @@ impl Songbird {
             ]
             .spacing(10)
             .padding(20),
+            column![
+                background_label,
+                notifications_toggle,
+                clipboard_toggle,
+                tray_toggle,
+                tray_status_display,
+            ]
+            .spacing(10)
+            .padding(20),
+            column![
+                sponsorblock_label,
+                sponsorblock_info,
+                sponsorblock_toggle,
+                sponsorblock_categories,
+                sponsorblock_url_input,
+            ]
+            .spacing(10)
+            .padding(20),
+            column![
+                loudness_label,
+                loudness_info,
+                loudness_controls,
+            ]
+            .spacing(10)
+            .padding(20),
+            column![
+                api_label,
+                api_info,
+                api_controls,
+                api_token,
+            ]
+            .spacing(10)
+            .padding(20),
+            column![
+                shortcuts_label,
+                shortcuts_info,
+                shortcuts_list,
+            ]
+            .spacing(10)
+            .padding(20),
+            column![
+                diagnostics_label,
+                diagnostics_info,
+                diagnostics_button,
+            ]
+            .spacing(10)
+            .padding(20),
         ]
         .spacing(20)
         .width(Length::Fill);
@@ -1591,8 +6018,19 @@ impl Songbird {
             .on_press(Message::CopyLogs(video_id.to_string()))
             .padding(10);
         
-        let header = row![back_button, title, copy_button]
+        let diagnostics_button = button(text("🩺 Run Diagnostics"))
+            .on_press(Message::ShowDiagnostics)
+            .padding(10);
+        
+        let copy_status = text(self.logs_copy_status.clone().unwrap_or_default())
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            });
+        
+        let header = row![back_button, title, copy_button, diagnostics_button, copy_status]
             .spacing(20)
+            .align_y(iced::Alignment::Center)
             .width(Length::Fill);
         
         let logs = self.download_logs.get(video_id);
@@ -1630,6 +6068,107 @@ impl Songbird {
             .into()
     }
     
+    fn diagnostics_view(&self) -> Element<'_, Message> {
+        let title = text("Diagnostics")
+            .size(28);
+        
+        let back_button = button(text("← Back"))
+            .on_press(Message::CloseDiagnostics)
+            .padding(10);
+        
+        let rerun_button = button(text(if self.diagnostics_running { "Running..." } else { "🔄 Run Again" }))
+            .on_press_maybe(if self.diagnostics_running {
+                None
+            } else {
+                Some(Message::RunDiagnostics)
+            })
+            .padding(10);
+        
+        let has_report = !self.diagnostics_running && !self.diagnostics.is_empty();
+        
+        let copy_button = button(text("📋 Copy Report"))
+            .on_press_maybe(has_report.then_some(Message::CopyDiagnosticsReport))
+            .padding(10);
+        
+        let save_button = button(text("💾 Save Report"))
+            .on_press_maybe(has_report.then_some(Message::SaveDiagnosticsReport))
+            .padding(10);
+        
+        let header = row![back_button, title, rerun_button, copy_button, save_button]
+            .spacing(20)
+            .width(Length::Fill);
+        
+        let mut checks_list = column![].spacing(15);
+        
+        if self.diagnostics_running {
+            checks_list = checks_list.push(
+                text("Running checks...")
+                    .size(16)
+                    .style(|_theme| text::Style {
+                        color: Some(iced::Color::from_rgb(0.4, 0.6, 0.9)),
+                    }),
+            );
+        } else {
+            for check in &self.diagnostics {
+                let passed = check.passed;
+                let mut check_column = column![
+                    text(format!("{} {}", if passed { "✅" } else { "❌" }, check.name))
+                        .size(16),
+                    text(&check.detail)
+                        .size(13)
+                        .style(move |_theme| text::Style {
+                            color: Some(if passed {
+                                iced::Color::from_rgb(0.6, 0.6, 0.6)
+                            } else {
+                                iced::Color::from_rgb(0.8, 0.2, 0.2)
+                            }),
+                        }),
+                ]
+                .spacing(4);
+                
+                if let Some(hint) = &check.hint {
+                    check_column = check_column.push(
+                        text(format!("Fix: {}", hint))
+                            .size(13)
+                            .style(|_theme| text::Style {
+                                color: Some(iced::Color::from_rgb(0.7, 0.5, 0.3)),
+                            }),
+                    );
+                }
+                
+                checks_list = checks_list.push(check_column);
+            }
+        }
+        
+        let mut content = column![header].spacing(20).width(Length::Fill).height(Length::Fill);
+        
+        if let Some(status) = &self.diagnostics_status {
+            content = content.push(
+                text(status)
+                    .size(12)
+                    .style(|_theme| text::Style {
+                        color: Some(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+                    }),
+            );
+        }
+        
+        let content = content.push(
+            scrollable(checks_list)
+                .width(Length::Fill)
+                .height(Length::Fill),
+        );
+        
+        container(content)
+            .padding(20)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(|_theme| container::Style {
+                background: Some(iced::Background::Color(iced::Color::from_rgb(0.1, 0.1, 0.1))),
+                ..Default::default()
+            })
+            .into()
+    }
+    
     fn rename_modal_view(&self, modal: &RenameModal) -> Element<'_, Message> {
         let title = text("Save As")
             .size(28);
@@ -1658,16 +6197,80 @@ impl Songbird {
         let buttons = row![cancel_button, download_button]
             .spacing(10);
         
-        let modal_content = column![
+        // Only fetch part of the video, e.g. one song out of a long mix
+        let section_row = row![
+            text("Only download from").size(14),
+            text_input("start", &modal.section_start)
+                .on_input(Message::RenameSectionStartChanged)
+                .on_submit(Message::ConfirmDownload)
+                .padding(6)
+                .width(Length::Fixed(90.0)),
+            text("to").size(14),
+            text_input("end", &modal.section_end)
+                .on_input(Message::RenameSectionEndChanged)
+                .on_submit(Message::ConfirmDownload)
+                .padding(6)
+                .width(Length::Fixed(90.0)),
+            text("(m:ss, optional)").size(12),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+        
+        // The link searched for pointed at a moment in the video
+        let section_row = match self.link_starts.get(&modal.video_id) {
+            Some(start) if modal.section_start.is_empty() => {
+                let start = format!("{}:{:02}", start / 60, start % 60);
+                section_row.push(
+                    button(text(format!("Start at {} (from the link)", start)).size(12))
+                        .on_press(Message::RenameSectionStartChanged(start))
+                        .padding(6),
+                )
+            }
+            _ => section_row,
+        };
+        
+        let chapter_count = self.video_chapters.get(&modal.video_id).map(|chapters| chapters.len());
+        let split_toggle = checkbox(
+            match chapter_count {
+                Some(count) => format!("Split into one file per chapter ({} chapters)", count),
+                None => "Split into one file per chapter, if the video has chapters".to_string(),
+            },
+            modal.split_chapters,
+        )
+        .on_toggle(Message::RenameSplitChaptersToggled);
+        
+        let lyrics_row = row![
+            checkbox("Save lyrics as .lrc", modal.lyrics.save_lrc)
+                .on_toggle(Message::RenameLyricsLrcToggled),
+            checkbox("Embed lyrics in the MP3", modal.lyrics.embed)
+                .on_toggle(Message::RenameLyricsEmbedToggled),
+        ]
+        .spacing(20);
+        
+        let mut modal_content = column![
             title,
             instruction,
             filename_input,
-            buttons,
+            section_row,
+            split_toggle,
+            lyrics_row,
         ]
         .spacing(20)
         .padding(30)
         .max_width(600);
         
+        if let Some(error) = &modal.section_error {
+            modal_content = modal_content.push(
+                text(error.clone())
+                    .size(14)
+                    .style(|_theme| text::Style {
+                        color: Some(iced::Color::from_rgb(0.9, 0.3, 0.3)),
+                    })
+            );
+        }
+        
+        let modal_content = modal_content.push(buttons);
+        
         container(modal_content)
             .width(Length::Fill)
             .height(Length::Fill)
@@ -1680,6 +6283,102 @@ impl Songbird {
             .into()
     }
     
+    fn trim_editor_view<'a>(&'a self, editor: &'a TrimEditor) -> Element<'a, Message> {
+        let title = text("Trim")
+            .size(28);
+        
+        let filename = editor.file_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        
+        let mut content = column![title, text(filename).size(14)]
+            .spacing(15)
+            .padding(30)
+            .max_width(700);
+        
+        if let Some(waveform) = &editor.waveform {
+            let settings = trim::TrimSettings {
+                start: editor.start,
+                end: editor.end,
+                fade_in: trim::parse_time(&editor.fade_in).unwrap_or(0.0),
+                fade_out: trim::parse_time(&editor.fade_out).unwrap_or(0.0),
+            };
+            
+            content = content.push(
+                canvas(trim::WaveformView { waveform, settings })
+                    .width(Length::Fill)
+                    .height(Length::Fixed(120.0))
+            );
+            
+            let duration = waveform.duration;
+            content = content.push(
+                row![
+                    text(format!("Start {}", trim::format_time(editor.start))).size(14).width(Length::Fixed(110.0)),
+                    slider(0.0..=duration, editor.start, Message::TrimStartChanged).step(0.1),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+            );
+            content = content.push(
+                row![
+                    text(format!("End {}", trim::format_time(editor.end))).size(14).width(Length::Fixed(110.0)),
+                    slider(0.0..=duration, editor.end, Message::TrimEndChanged).step(0.1),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+            );
+            content = content.push(
+                row![
+                    text("Fade in (s)").size(14),
+                    text_input("0", &editor.fade_in)
+                        .on_input(Message::TrimFadeInChanged)
+                        .padding(6)
+                        .width(Length::Fixed(70.0)),
+                    text("Fade out (s)").size(14),
+                    text_input("0", &editor.fade_out)
+                        .on_input(Message::TrimFadeOutChanged)
+                        .padding(6)
+                        .width(Length::Fixed(70.0)),
+                    text(format!("Keeps {}", trim::format_time(editor.end - editor.start))).size(14),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+            );
+        }
+        
+        if let Some(status) = &editor.status {
+            content = content.push(
+                text(status)
+                    .size(14)
+                    .style(|_theme| text::Style {
+                        color: Some(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+                    })
+            );
+        }
+        
+        let close_button = button(text("Close").size(16))
+            .on_press_maybe((!editor.busy).then_some(Message::CloseTrimEditor))
+            .padding(10);
+        
+        let apply_button = button(text("Apply Trim").size(16))
+            .on_press_maybe((!editor.busy && editor.waveform.is_some()).then_some(Message::ApplyTrim))
+            .padding(10);
+        
+        content = content.push(row![close_button, apply_button].spacing(10));
+        
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .style(|_theme| container::Style {
+                background: Some(iced::Background::Color(iced::Color::from_rgba(0.0, 0.0, 0.0, 0.8))),
+                ..Default::default()
+            })
+            .into()
+    }
+    
     fn player_logs_view(&self) -> Element<'_, Message> {
         let title = text("Player Logs")
             .size(28);
@@ -1692,8 +6391,15 @@ impl Songbird {
             .on_press(Message::CopyPlayerLogs)
             .padding(10);
         
-        let header = row![back_button, title, copy_button]
+        let copy_status = text(self.logs_copy_status.clone().unwrap_or_default())
+            .size(14)
+            .style(|_theme| text::Style {
+                color: Some(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            });
+        
+        let header = row![back_button, title, copy_button, copy_status]
             .spacing(20)
+            .align_y(iced::Alignment::Center)
             .width(Length::Fill);
         
         let logs_text = if self.player_logs.is_empty() {
@@ -1735,7 +6441,11 @@ impl Songbird {
             "Linux"
         };
         
-        let title = text(format!("Share File - {}", platform))
+        let title = text(if modal.file_count > 1 {
+            format!("Share {} Files - {}", modal.file_count, platform)
+        } else {
+            format!("Share File - {}", platform)
+        })
             .size(28);
         
         let instruction = if cfg!(target_os = "macos") {
@@ -1745,7 +6455,11 @@ impl Songbird {
                     color: Some(iced::Color::from_rgb(0.6, 0.6, 0.6)),
                 })
         } else {
-            text("Scan the QR code with your iPhone camera to download the file")
+            text(if modal.file_count > 1 {
+                "Scan the QR code with your iPhone camera to download the files"
+            } else {
+                "Scan the QR code with your iPhone camera to download the file"
+            })
                 .size(14)
                 .style(|_theme| text::Style {
                     color: Some(iced::Color::from_rgb(0.6, 0.6, 0.6)),
@@ -1758,15 +6472,26 @@ impl Songbird {
                 color: Some(iced::Color::from_rgb(0.4, 0.6, 0.9)),
             });
         
-        let qr_display = text(&modal.qr_code)
-            .size(10)
-            .font(iced::Font::MONOSPACE)
+        let qr_display: Element<'a, Message> = if let (Some(handle), Some(qr)) = (&modal.qr_handle, &modal.qr_image) {
+            Image::new(handle.clone())
+                .width(Length::Fixed(qr.width as f32))
+                .height(Length::Fixed(qr.height as f32))
+                .filter_method(image::FilterMethod::Nearest)
+                .into()
+        } else {
+            text(match modal.qr_mode {
+                QrMode::Url => "QR code generation failed",
+                QrMode::WifiJoin => "Enter the Wi-Fi network name to generate a QR code",
+            })
+            .size(14)
             .style(|_theme| text::Style {
-                color: Some(iced::Color::WHITE),
-            });
+                color: Some(iced::Color::from_rgb(0.3, 0.3, 0.3)),
+            })
+            .into()
+        };
         
         let qr_container = container(qr_display)
-            .padding(20)
+            .padding(10)
             .style(|_theme| container::Style {
                 background: Some(iced::Background::Color(iced::Color::WHITE)),
                 border: iced::Border {
@@ -1777,6 +6502,53 @@ impl Songbird {
                 ..Default::default()
             });
         
+        let mode_button = button(text(match modal.qr_mode {
+            QrMode::Url => "Show Wi-Fi QR",
+            QrMode::WifiJoin => "Show Download QR",
+        }).size(14))
+            .on_press(Message::ToggleQrMode)
+            .padding(8);
+        
+        let save_button = button(text("Save Image").size(14))
+            .on_press_maybe(modal.qr_image.as_ref().map(|_| Message::SaveQrCode))
+            .padding(8);
+        
+        let copy_button = button(text("Copy Image").size(14))
+            .on_press_maybe(modal.qr_image.as_ref().map(|_| Message::CopyQrCode))
+            .padding(8);
+        
+        let mut qr_controls = column![
+            row![mode_button, save_button, copy_button].spacing(10),
+        ]
+        .spacing(10);
+        
+        if modal.qr_mode == QrMode::WifiJoin {
+            qr_controls = qr_controls.push(
+                row![
+                    text_input("Wi-Fi network name", self.config.wifi_ssid.as_deref().unwrap_or(""))
+                        .on_input(Message::WifiSsidChanged)
+                        .padding(8)
+                        .size(14),
+                    text_input("Password", self.config.wifi_password.as_deref().unwrap_or(""))
+                        .on_input(Message::WifiPasswordChanged)
+                        .secure(true)
+                        .padding(8)
+                        .size(14),
+                ]
+                .spacing(10),
+            );
+        }
+        
+        if let Some(status) = &modal.qr_status {
+            qr_controls = qr_controls.push(
+                text(status)
+                    .size(12)
+                    .style(|_theme| text::Style {
+                        color: Some(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+                    }),
+            );
+        }
+        
         let info_text = text("Note: The file will download to your iPhone's Downloads folder.\nIt won't be added to the Music app automatically.")
             .size(12)
             .style(|_theme| text::Style {
@@ -1787,18 +6559,24 @@ impl Songbird {
             .on_press(Message::CloseShare)
             .padding(10);
         
-        let modal_content = column![
+        let mut modal_content = column![
             title,
             instruction,
             url_text,
             qr_container,
+            qr_controls,
             info_text,
-            close_button,
         ]
         .spacing(20)
         .padding(30)
         .max_width(600);
         
+        if let Some(requests) = self.phone_requests_view() {
+            modal_content = modal_content.push(requests);
+        }
+        
+        let modal_content = modal_content.push(close_button);
+        
         container(modal_content)
             .width(Length::Fill)
             .height(Length::Fill)
@@ -1811,3 +6589,43 @@ impl Songbird {
             .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parses_progress_lines() {
+        let progress = DownloadProgress::parse("download:1048576/4194304/NA/524288.5/6/NA/NA").unwrap();
+        assert_eq!(progress.downloaded_bytes, 1048576);
+        assert_eq!(progress.total_bytes, Some(4194304));
+        assert_eq!(progress.speed, Some(524288.5));
+        assert_eq!(progress.eta, Some(6));
+        assert_eq!(progress.fragments, None);
+        assert_eq!(progress.percent(), Some(25.0));
+    }
+    
+    #[test]
+    fn falls_back_to_estimates_and_fragments() {
+        let progress = DownloadProgress::parse("download:2000/NA/8000.0/NA/NA/NA/NA").unwrap();
+        assert_eq!(progress.total_bytes, Some(8000));
+        assert_eq!(progress.speed, None);
+        
+        let progress = DownloadProgress::parse("download:512/NA/NA/NA/NA/3/12").unwrap();
+        assert_eq!(progress.total_bytes, None);
+        assert_eq!(progress.fragments, Some((3, 12)));
+        assert_eq!(progress.percent(), Some(25.0));
+        
+        let progress = DownloadProgress::parse("download:512/0/NA/NA/NA/NA/0").unwrap();
+        assert_eq!(progress.total_bytes, None);
+        assert_eq!(progress.fragments, None);
+        assert_eq!(progress.percent(), None);
+    }
+    
+    #[test]
+    fn rejects_other_lines() {
+        assert!(DownloadProgress::parse("[download] Destination: song.webm").is_none());
+        assert!(DownloadProgress::parse("download:NA/NA/NA/NA/NA/NA/NA").is_none());
+        assert!(DownloadProgress::parse("download:1/2/3").is_none());
+    }
+}
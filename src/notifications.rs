@@ -0,0 +1,62 @@
+use notify_rust::Notification;
+use std::path::{Path, PathBuf};
+
+const SHOW_IN_FOLDER_ACTION: &str = "show-in-folder";
+
+// Desktop notification for a finished download, offering to reveal the file
+pub fn download_finished(title: &str, file_path: &Path) {
+    let title = title.to_string();
+    let file_path = file_path.to_path_buf();
+    
+    // Showing and waiting on a notification blocks, so keep it off the UI thread
+    std::thread::spawn(move || {
+        let mut notification = Notification::new();
+        notification
+            .summary("Download complete")
+            .body(&title)
+            .appname("Nightingale");
+        
+        // Linux and macOS wait for the action; elsewhere the notification is only shown
+        #[cfg(unix)]
+        {
+            notification.action(SHOW_IN_FOLDER_ACTION, "Show in Folder");
+            if let Ok(handle) = notification.show() {
+                handle.wait_for_action(|action| {
+                    if action == SHOW_IN_FOLDER_ACTION {
+                        show_in_folder(&file_path);
+                    }
+                });
+            }
+        }
+        
+        #[cfg(not(unix))]
+        {
+            let _ = file_path;
+            let _ = notification.show();
+        }
+    });
+}
+
+// Desktop notification for a download that failed after all retries
+pub fn download_failed(title: &str, error: &str) {
+    let title = title.to_string();
+    let error = error.to_string();
+    
+    std::thread::spawn(move || {
+        let _ = Notification::new()
+            .summary("Download failed")
+            .body(&format!("{}\n{}", title, error))
+            .appname("Nightingale")
+            .show();
+    });
+}
+
+// Open the folder containing a file, selecting the file where the platform supports it
+pub fn show_in_folder(file_path: &Path) {
+    if cfg!(target_os = "macos") {
+        let _ = std::process::Command::new("open").arg("-R").arg(file_path).spawn();
+    } else {
+        let folder = file_path.parent().map(PathBuf::from).unwrap_or_else(|| file_path.to_path_buf());
+        let _ = open::that(folder);
+    }
+}
@@ -0,0 +1,62 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+// Public SponsorBlock instance; a local mirror can be set in Settings
+pub const DEFAULT_API_URL: &str = "https://sponsor.ajay.app";
+
+// Categories that can be removed, with the label shown in Settings
+pub const CATEGORIES: &[(&str, &str)] = &[
+    ("music_offtopic", "Non-music sections"),
+    ("intro", "Intros"),
+    ("outro", "Outros"),
+];
+
+// Server and categories to remove, taken from the config when a download starts
+#[derive(Debug, Clone)]
+pub struct SponsorBlock {
+    pub api_url: String,
+    pub categories: Vec<String>,
+}
+
+// A marked range of the video, in seconds
+#[derive(Debug, Clone, Deserialize)]
+pub struct Segment {
+    pub category: String,
+    #[serde(rename = "segment")]
+    pub range: (f64, f64),
+}
+
+impl SponsorBlock {
+    // Segments marked for the video in the chosen categories; none is not an error
+    pub async fn fetch_segments(&self, video_id: &str) -> Result<Vec<Segment>, String> {
+        let categories = serde_json::to_string(&self.categories)
+            .map_err(|e| format!("Failed to encode categories: {}", e))?;
+        
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        
+        let response = client
+            .get(format!("{}/api/skipSegments", self.api_url.trim_end_matches('/')))
+            .query(&[("videoID", video_id), ("categories", categories.as_str())])
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach {}: {}", self.api_url, e))?;
+        
+        // The API answers 404 when nothing has been submitted for the video
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        
+        let mut segments: Vec<Segment> = response
+            .error_for_status()
+            .map_err(|e| format!("Segment lookup failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse segments: {}", e))?;
+        
+        segments.sort_by(|a, b| a.range.0.total_cmp(&b.range.0));
+        Ok(segments)
+    }
+}
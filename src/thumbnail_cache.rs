@@ -0,0 +1,123 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::sync::Semaphore;
+
+// Total size of cached thumbnails before the least recently used are removed
+const MAX_CACHE_BYTES: u64 = 50 * 1024 * 1024;
+
+// Thumbnail downloads in flight at once; a search returns 20+ results
+const MAX_CONCURRENT_FETCHES: usize = 6;
+
+// Widest thumbnail worth fetching for a 120px preview on a high-DPI screen
+const MAX_THUMBNAIL_WIDTH: u64 = 480;
+
+static FETCH_PERMITS: Semaphore = Semaphore::const_new(MAX_CONCURRENT_FETCHES);
+
+fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("nightingale").join("thumbnails"))
+}
+
+fn cache_path(video_id: &str) -> Option<PathBuf> {
+    // Video ids are [A-Za-z0-9_-]; anything else must not reach the filesystem
+    if video_id.is_empty() || !video_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return None;
+    }
+    cache_dir().map(|dir| dir.join(format!("{}.img", video_id)))
+}
+
+// Pick the largest thumbnail no wider than MAX_THUMBNAIL_WIDTH from a YouTube/yt-dlp thumbnails array,
+// falling back to the smallest one when they are all wider
+pub fn best_thumbnail(thumbnails: &serde_json::Value) -> Option<String> {
+    let candidates: Vec<(u64, &str)> = thumbnails.as_array()?
+        .iter()
+        .filter_map(|t| Some((t["width"].as_u64().unwrap_or(0), t["url"].as_str()?)))
+        .collect();
+    
+    candidates.iter()
+        .filter(|(width, _)| *width <= MAX_THUMBNAIL_WIDTH)
+        .max_by_key(|(width, _)| *width)
+        .or_else(|| candidates.iter().min_by_key(|(width, _)| *width))
+        .map(|(_, url)| url.to_string())
+}
+
+// Thumbnail bytes for a video, from the disk cache or downloaded and cached
+pub async fn load(video_id: String, url: String) -> Result<Vec<u8>, String> {
+    let path = cache_path(&video_id);
+    
+    if let Some(path) = &path {
+        if let Ok(bytes) = tokio::fs::read(path).await {
+            touch(path);
+            return Ok(bytes);
+        }
+    }
+    
+    if url.is_empty() {
+        return Err("No thumbnail available".to_string());
+    }
+    
+    let bytes = {
+        let _permit = FETCH_PERMITS.acquire().await
+            .map_err(|e| format!("Failed to queue thumbnail download: {}", e))?;
+        
+        reqwest::get(&url)
+            .await
+            .map_err(|e| format!("Failed to download thumbnail: {}", e))?
+            .error_for_status()
+            .map_err(|e| format!("Failed to download thumbnail: {}", e))?
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read thumbnail bytes: {}", e))?
+            .to_vec()
+    };
+    
+    // Caching is best-effort; a failure only means fetching it again next time
+    if let Some(path) = path {
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        if std::fs::write(&path, &bytes).is_ok() {
+            evict(path.parent().unwrap_or(Path::new(".")));
+        }
+    }
+    
+    Ok(bytes)
+}
+
+// Mark a cached thumbnail as recently used; the modification time is the LRU clock
+fn touch(path: &Path) {
+    if let Ok(file) = std::fs::File::options().write(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+// Remove the least recently used thumbnails until the cache fits in MAX_CACHE_BYTES
+fn evict(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    
+    let mut files: Vec<(SystemTime, u64, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            metadata.is_file().then(|| {
+                (metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH), metadata.len(), entry.path())
+            })
+        })
+        .collect();
+    
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    if total <= MAX_CACHE_BYTES {
+        return;
+    }
+    
+    files.sort_by_key(|(modified, _, _)| *modified);
+    for (_, size, path) in files {
+        if total <= MAX_CACHE_BYTES {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            total -= size;
+        }
+    }
+}
@@ -0,0 +1,279 @@
+// Menu actions chosen from the tray icon, polled by the app
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrayEvent {
+    ShowWindow,
+    Quit,
+}
+
+// Tray icon size in pixels
+const ICON_SIZE: u32 = 32;
+
+// How long startup waits for the tray to register before carrying on without it
+#[cfg(target_os = "linux")]
+const READY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+// The songbird artwork scaled down to tray size, as RGBA
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn icon_rgba() -> Result<(Vec<u8>, u32, u32), String> {
+    let icon = image::load_from_memory(include_bytes!("../assets/songbird.png"))
+        .map_err(|e| format!("Failed to load tray icon: {}", e))?
+        .resize_exact(ICON_SIZE, ICON_SIZE, image::imageops::FilterType::Lanczos3)
+        .to_rgba8();
+    Ok((icon.into_raw(), ICON_SIZE, ICON_SIZE))
+}
+
+#[cfg(target_os = "linux")]
+pub use linux::Tray;
+
+#[cfg(target_os = "macos")]
+pub use macos::Tray;
+
+// StatusNotifierItem over D-Bus, served from its own thread and runtime
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::*;
+    use ksni::TrayMethods;
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::mpsc;
+    
+    struct NightingaleTray {
+        status: String,
+        icon: ksni::Icon,
+        events: Arc<Mutex<Vec<TrayEvent>>>,
+    }
+    
+    impl NightingaleTray {
+        fn push(&self, event: TrayEvent) {
+            if let Ok(mut events) = self.events.lock() {
+                events.push(event);
+            }
+        }
+    }
+    
+    impl ksni::Tray for NightingaleTray {
+        fn id(&self) -> String {
+            "nightingale".to_string()
+        }
+        
+        fn title(&self) -> String {
+            "Nightingale".to_string()
+        }
+        
+        fn icon_pixmap(&self) -> Vec<ksni::Icon> {
+            vec![self.icon.clone()]
+        }
+        
+        fn tool_tip(&self) -> ksni::ToolTip {
+            ksni::ToolTip {
+                title: "Nightingale".to_string(),
+                description: self.status.clone(),
+                ..Default::default()
+            }
+        }
+        
+        fn activate(&mut self, _x: i32, _y: i32) {
+            self.push(TrayEvent::ShowWindow);
+        }
+        
+        fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
+            use ksni::menu::StandardItem;
+            vec![
+                StandardItem {
+                    label: self.status.clone(),
+                    enabled: false,
+                    ..Default::default()
+                }
+                .into(),
+                ksni::MenuItem::Separator,
+                StandardItem {
+                    label: "Show Nightingale".to_string(),
+                    activate: Box::new(|tray: &mut Self| tray.push(TrayEvent::ShowWindow)),
+                    ..Default::default()
+                }
+                .into(),
+                StandardItem {
+                    label: "Quit".to_string(),
+                    activate: Box::new(|tray: &mut Self| tray.push(TrayEvent::Quit)),
+                    ..Default::default()
+                }
+                .into(),
+            ]
+        }
+    }
+    
+    pub struct Tray {
+        status: String,
+        status_tx: mpsc::UnboundedSender<String>,
+        events: Arc<Mutex<Vec<TrayEvent>>>,
+    }
+    
+    impl Tray {
+        pub fn new() -> Result<Self, String> {
+            let (rgba, width, height) = icon_rgba()?;
+            
+            // StatusNotifierItem wants ARGB32 in network byte order
+            let data = rgba
+                .chunks_exact(4)
+                .flat_map(|p| [p[3], p[0], p[1], p[2]])
+                .collect();
+            
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let tray = NightingaleTray {
+                status: "Idle".to_string(),
+                icon: ksni::Icon {
+                    width: width as i32,
+                    height: height as i32,
+                    data,
+                },
+                events: events.clone(),
+            };
+            
+            let (status_tx, mut status_rx) = mpsc::unbounded_channel::<String>();
+            let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+            
+            std::thread::spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(format!("Failed to start tray runtime: {}", e)));
+                        return;
+                    }
+                };
+                
+                runtime.block_on(async move {
+                    let handle = match tray.spawn().await {
+                        Ok(handle) => {
+                            let _ = ready_tx.send(Ok(()));
+                            handle
+                        }
+                        Err(e) => {
+                            let _ = ready_tx.send(Err(format!("No system tray available: {}", e)));
+                            return;
+                        }
+                    };
+                    
+                    // Runs until the Tray is dropped and the sender goes away
+                    while let Some(status) = status_rx.recv().await {
+                        handle.update(|tray| tray.status = status).await;
+                    }
+                    
+                    handle.shutdown().await;
+                });
+            });
+            
+            // A D-Bus session that never answers mustn't hold up the window
+            ready_rx
+                .recv_timeout(READY_TIMEOUT)
+                .map_err(|e| match e {
+                    std::sync::mpsc::RecvTimeoutError::Timeout => "The system tray did not respond".to_string(),
+                    std::sync::mpsc::RecvTimeoutError::Disconnected => "Tray thread exited unexpectedly".to_string(),
+                })??;
+            
+            Ok(Self {
+                status: "Idle".to_string(),
+                status_tx,
+                events,
+            })
+        }
+        
+        // Update the aggregate progress shown in the tooltip and menu
+        pub fn set_status(&mut self, status: &str) {
+            if self.status != status {
+                self.status = status.to_string();
+                let _ = self.status_tx.send(self.status.clone());
+            }
+        }
+        
+        pub fn take_events(&self) -> Vec<TrayEvent> {
+            self.events
+                .lock()
+                .map(|mut events| std::mem::take(&mut *events))
+                .unwrap_or_default()
+        }
+    }
+}
+
+// NSStatusItem via tray-icon; must live on the main thread
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::*;
+    use tray_icon::menu::{Menu, MenuEvent, MenuItem};
+    use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+    
+    pub struct Tray {
+        icon: TrayIcon,
+        status: String,
+        status_item: MenuItem,
+        show_item: MenuItem,
+        quit_item: MenuItem,
+    }
+    
+    impl Tray {
+        pub fn new() -> Result<Self, String> {
+            let (rgba, width, height) = icon_rgba()?;
+            let image = Icon::from_rgba(rgba, width, height)
+                .map_err(|e| format!("Failed to load tray icon: {}", e))?;
+            
+            let status_item = MenuItem::new("Idle", false, None);
+            let show_item = MenuItem::new("Show Nightingale", true, None);
+            let quit_item = MenuItem::new("Quit", true, None);
+            
+            let menu = Menu::new();
+            menu.append_items(&[&status_item, &show_item, &quit_item])
+                .map_err(|e| format!("Failed to build tray menu: {}", e))?;
+            
+            let icon = TrayIconBuilder::new()
+                .with_icon(image)
+                .with_tooltip("Nightingale")
+                .with_menu(Box::new(menu))
+                .build()
+                .map_err(|e| format!("No system tray available: {}", e))?;
+            
+            Ok(Self {
+                icon,
+                status: "Idle".to_string(),
+                status_item,
+                show_item,
+                quit_item,
+            })
+        }
+        
+        // Update the aggregate progress shown next to the menu bar icon
+        pub fn set_status(&mut self, status: &str) {
+            if self.status != status {
+                self.status = status.to_string();
+                self.status_item.set_text(status);
+                let _ = self.icon.set_tooltip(Some(format!("Nightingale - {}", status)));
+            }
+        }
+        
+        pub fn take_events(&self) -> Vec<TrayEvent> {
+            let mut events = Vec::new();
+            while let Ok(event) = MenuEvent::receiver().try_recv() {
+                if event.id == *self.show_item.id() {
+                    events.push(TrayEvent::ShowWindow);
+                } else if event.id == *self.quit_item.id() {
+                    events.push(TrayEvent::Quit);
+                }
+            }
+            events
+        }
+    }
+}
+
+// No tray support on other platforms; the app keeps its normal close behaviour
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub struct Tray;
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+impl Tray {
+    pub fn new() -> Result<Self, String> {
+        Err("System tray is not supported on this platform".to_string())
+    }
+    
+    pub fn set_status(&mut self, _status: &str) {}
+    
+    pub fn take_events(&self) -> Vec<TrayEvent> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,266 @@
+use iced::widget::canvas::{self, Frame, Geometry, Path, Stroke};
+use iced::{mouse, Color, Point, Rectangle, Renderer, Size, Theme};
+use crate::audio_format::AudioFormat;
+use std::path::PathBuf;
+
+// Sample rate the audio is decoded at for the waveform; plenty for peak display
+const WAVEFORM_SAMPLE_RATE: u32 = 8000;
+
+// Number of bars drawn across the waveform
+const WAVEFORM_BUCKETS: usize = 600;
+
+// Peak level per slice of the file, for display
+#[derive(Debug, Clone)]
+pub struct Waveform {
+    pub peaks: Vec<f32>, // 0.0..=1.0
+    pub duration: f64, // seconds
+}
+
+// Trim points and fades, in seconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrimSettings {
+    pub start: f64,
+    pub end: f64,
+    pub fade_in: f64,
+    pub fade_out: f64,
+}
+
+// Decode the file to mono PCM with ffmpeg and reduce it to peak levels
+pub async fn load_waveform(ffmpeg: PathBuf, file: PathBuf) -> Result<Waveform, String> {
+    let output = tokio::process::Command::new(&ffmpeg)
+        .arg("-v").arg("error")
+        .arg("-i").arg(&file)
+        .arg("-ac").arg("1")
+        .arg("-ar").arg(WAVEFORM_SAMPLE_RATE.to_string())
+        .arg("-f").arg("s16le")
+        .arg("-")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg could not read {}: {}",
+            file.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    
+    let samples: Vec<i16> = output.stdout
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+        .collect();
+    
+    if samples.is_empty() {
+        return Err("The file contains no audio".to_string());
+    }
+    
+    let bucket_size = samples.len().div_ceil(WAVEFORM_BUCKETS);
+    let peaks = samples
+        .chunks(bucket_size)
+        .map(|chunk| {
+            let peak = chunk.iter().map(|s| s.unsigned_abs()).max().unwrap_or(0);
+            peak as f32 / i16::MAX as f32
+        })
+        .collect();
+    
+    Ok(Waveform {
+        peaks,
+        duration: samples.len() as f64 / WAVEFORM_SAMPLE_RATE as f64,
+    })
+}
+
+// Re-encode the file with only the trimmed range and the fades, replacing the original
+pub async fn apply_trim(ffmpeg: PathBuf, file: PathBuf, settings: TrimSettings) -> Result<(), String> {
+    if settings.end <= settings.start {
+        return Err("The end point must be after the start point".to_string());
+    }
+    
+    let length = settings.end - settings.start;
+    let mut filters = vec![
+        format!("atrim=start={:.3}:end={:.3}", settings.start, settings.end),
+        "asetpts=PTS-STARTPTS".to_string(),
+    ];
+    if settings.fade_in > 0.0 {
+        filters.push(format!("afade=t=in:st=0:d={:.3}", settings.fade_in.min(length)));
+    }
+    if settings.fade_out > 0.0 {
+        let fade_out = settings.fade_out.min(length);
+        filters.push(format!("afade=t=out:st={:.3}:d={:.3}", length - fade_out, fade_out));
+    }
+    
+    let format = AudioFormat::from_path(&file);
+    let staged = file.with_extension(format!("trim.{}", format.extension()));
+    
+    // Keep the embedded cover art and tags
+    let output = tokio::process::Command::new(&ffmpeg)
+        .arg("-y")
+        .arg("-v").arg("error")
+        .arg("-i").arg(&file)
+        .arg("-map").arg("0")
+        .arg("-map_metadata").arg("0")
+        .arg("-c:v").arg("copy")
+        .arg("-af").arg(filters.join(","))
+        .args(format.encoder_args())
+        .args(format.tag_args())
+        .arg(&staged)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&staged);
+        return Err(format!("ffmpeg failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    
+    std::fs::rename(&staged, &file)
+        .map_err(|e| format!("Failed to replace {}: {}", file.display(), e))
+}
+
+// Parse "m:ss", "h:mm:ss" or plain seconds, allowing fractions
+pub fn parse_time(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    
+    value.split(':').try_fold(0.0, |total, part| {
+        part.trim().parse::<f64>().ok().filter(|n| n.is_finite() && *n >= 0.0).map(|n| total * 60.0 + n)
+    })
+}
+
+// yt-dlp --download-sections value for an optional start and end; None downloads everything
+pub fn download_section(start: &str, end: &str) -> Result<Option<String>, String> {
+    if start.trim().is_empty() && end.trim().is_empty() {
+        return Ok(None);
+    }
+    
+    let start = match start.trim() {
+        "" => 0.0,
+        value => parse_time(value).ok_or_else(|| format!("Invalid start time: {}", value))?,
+    };
+    let end = match end.trim() {
+        "" => return Ok(Some(format!("*{:.3}-inf", start))),
+        value => parse_time(value).ok_or_else(|| format!("Invalid end time: {}", value))?,
+    };
+    
+    if end <= start {
+        return Err("The end time must be after the start time".to_string());
+    }
+    Ok(Some(format!("*{:.3}-{:.3}", start, end)))
+}
+
+pub fn format_time(seconds: f64) -> String {
+    // Round once up front so 59.96 carries over to 1:00.0 rather than showing 0:60.0
+    let tenths = (seconds.max(0.0) * 10.0).round() as u64;
+    format!("{}:{:02}.{}", tenths / 600, tenths % 600 / 10, tenths % 10)
+}
+
+// Waveform with the kept range highlighted and the fades drawn as ramps
+pub struct WaveformView<'a> {
+    pub waveform: &'a Waveform,
+    pub settings: TrimSettings,
+}
+
+impl<Message> canvas::Program<Message> for WaveformView<'_> {
+    type State = ();
+    
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let width = bounds.width;
+        let height = bounds.height;
+        let middle = height / 2.0;
+        
+        frame.fill_rectangle(Point::ORIGIN, bounds.size(), Color::from_rgb(0.12, 0.12, 0.15));
+        
+        let duration = self.waveform.duration.max(0.001);
+        let to_x = |seconds: f64| (seconds / duration).clamp(0.0, 1.0) as f32 * width;
+        let start_x = to_x(self.settings.start);
+        let end_x = to_x(self.settings.end);
+        
+        frame.fill_rectangle(
+            Point::new(start_x, 0.0),
+            Size::new((end_x - start_x).max(0.0), height),
+            Color::from_rgb(0.17, 0.2, 0.28),
+        );
+        
+        let bar_width = width / self.waveform.peaks.len().max(1) as f32;
+        for (index, peak) in self.waveform.peaks.iter().enumerate() {
+            let x = index as f32 * bar_width;
+            let bar_height = (peak * (height - 4.0)).max(1.0);
+            let kept = x + bar_width > start_x && x < end_x;
+            
+            frame.fill_rectangle(
+                Point::new(x, middle - bar_height / 2.0),
+                Size::new(bar_width.max(1.0), bar_height),
+                if kept {
+                    Color::from_rgb(0.4, 0.6, 0.9)
+                } else {
+                    Color::from_rgb(0.3, 0.3, 0.35)
+                },
+            );
+        }
+        
+        // Fade ramps from silence at the edge to full level
+        let ramp = Stroke::default().with_width(2.0).with_color(Color::from_rgb(0.9, 0.7, 0.2));
+        if self.settings.fade_in > 0.0 {
+            let fade_end = to_x(self.settings.start + self.settings.fade_in);
+            frame.stroke(&Path::line(Point::new(start_x, height), Point::new(fade_end, 0.0)), ramp);
+        }
+        if self.settings.fade_out > 0.0 {
+            let fade_start = to_x(self.settings.end - self.settings.fade_out);
+            frame.stroke(&Path::line(Point::new(fade_start, 0.0), Point::new(end_x, height)), ramp);
+        }
+        
+        let marker = Stroke::default().with_width(2.0).with_color(Color::WHITE);
+        for x in [start_x, end_x] {
+            frame.stroke(&Path::line(Point::new(x, 0.0), Point::new(x, height)), marker);
+        }
+        
+        vec![frame.into_geometry()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parses_times() {
+        assert_eq!(parse_time("90"), Some(90.0));
+        assert_eq!(parse_time("1:30.5"), Some(90.5));
+        assert_eq!(parse_time(" 1:02:03 "), Some(3723.0));
+        assert_eq!(parse_time(""), None);
+        assert_eq!(parse_time("1:-5"), None);
+        assert_eq!(parse_time("1m30s"), None);
+        assert_eq!(parse_time("inf"), None);
+        assert_eq!(parse_time("1:NaN"), None);
+    }
+    
+    #[test]
+    fn builds_download_sections() {
+        assert_eq!(download_section("", ""), Ok(None));
+        assert_eq!(download_section("1:00", "2:30"), Ok(Some("*60.000-150.000".to_string())));
+        assert_eq!(download_section("", "0:45"), Ok(Some("*0.000-45.000".to_string())));
+        assert_eq!(download_section("30", " "), Ok(Some("*30.000-inf".to_string())));
+        assert!(download_section("2:00", "1:00").is_err());
+        assert!(download_section("abc", "1:00").is_err());
+        assert!(download_section("0:10", "later").is_err());
+    }
+    
+    #[test]
+    fn formats_times() {
+        assert_eq!(format_time(0.0), "0:00.0");
+        assert_eq!(format_time(75.24), "1:15.2");
+        assert_eq!(format_time(-3.0), "0:00.0");
+        assert_eq!(format_time(59.96), "1:00.0");
+        assert_eq!(format_time(119.97), "2:00.0");
+    }
+}
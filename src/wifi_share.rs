@@ -1,40 +1,157 @@
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::net::{TcpListener, IpAddr};
-use tiny_http::{Server, Response, Header};
-use qrcode::QrCode;
-use qrcode::render::unicode;
+use tiny_http::{Server, Response, Header, Method};
+use qrcode::{Color, QrCode};
+
+// Pixel size of one QR module, and the quiet zone around the code in modules
+const QR_MODULE_SIZE: u32 = 8;
+const QR_QUIET_ZONE: u32 = 4;
+
+// Largest request form body read from the phone; a link or search fits many times over
+const MAX_REQUEST_BODY: u64 = 4096;
+
+// Requests from the phone waiting to be approved, here and in the app together
+pub const MAX_PENDING_REQUESTS: usize = 20;
+
+// An RGBA bitmap of a rendered QR code
+#[derive(Debug, Clone)]
+pub struct QrImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl QrImage {
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        image::save_buffer(path, &self.pixels, self.width, self.height, image::ColorType::Rgba8)
+            .map_err(|e| format!("Failed to save QR code: {}", e))
+    }
+}
+
+// Render data as a QR code bitmap with crisp square modules and a standard quiet zone
+pub fn render_qr_code(data: &str) -> Result<QrImage, String> {
+    let code = QrCode::new(data.as_bytes())
+        .map_err(|e| format!("Failed to generate QR code: {}", e))?;
+    
+    let modules = code.width() as u32;
+    let colors = code.to_colors();
+    let size = (modules + QR_QUIET_ZONE * 2) * QR_MODULE_SIZE;
+    let mut pixels = vec![255u8; (size * size * 4) as usize];
+    
+    for (index, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        
+        let module_x = index as u32 % modules + QR_QUIET_ZONE;
+        let module_y = index as u32 / modules + QR_QUIET_ZONE;
+        
+        for y in module_y * QR_MODULE_SIZE..(module_y + 1) * QR_MODULE_SIZE {
+            for x in module_x * QR_MODULE_SIZE..(module_x + 1) * QR_MODULE_SIZE {
+                let offset = ((y * size + x) * 4) as usize;
+                pixels[offset..offset + 3].copy_from_slice(&[0, 0, 0]);
+            }
+        }
+    }
+    
+    Ok(QrImage {
+        width: size,
+        height: size,
+        pixels,
+    })
+}
+
+// Build the payload phones understand for joining a Wi-Fi network from a QR code
+pub fn wifi_join_payload(ssid: &str, password: &str) -> String {
+    fn escape(value: &str) -> String {
+        value.chars().fold(String::new(), |mut escaped, c| {
+            if matches!(c, '\\' | ';' | ',' | ':' | '"') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+            escaped
+        })
+    }
+    
+    if password.is_empty() {
+        format!("WIFI:T:nopass;S:{};;", escape(ssid))
+    } else {
+        format!("WIFI:T:WPA;S:{};P:{};;", escape(ssid), escape(password))
+    }
+}
+
+// Best-effort lookup of the Wi-Fi network this machine is connected to
+pub fn current_wifi_ssid() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let output = std::process::Command::new("nmcli")
+            .args(["-t", "-f", "active,ssid", "dev", "wifi"])
+            .output()
+            .ok()?;
+        
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.strip_prefix("yes:"))
+            .filter(|ssid| !ssid.is_empty())
+            .map(|ssid| ssid.to_string())
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
 
 #[derive(Clone)]
-pub struct ShareServer {
-    file_path: PathBuf,
+struct SharedFile {
+    path: PathBuf,
     filename: String,
+}
+
+#[derive(Clone)]
+pub struct ShareServer {
+    files: Vec<SharedFile>,
     port: u16,
     running: Arc<Mutex<bool>>,
+    requests: Arc<Mutex<Vec<String>>>,
+    held: Arc<AtomicUsize>, // requests already taken by the app and still awaiting approval
 }
 
 impl ShareServer {
-    pub fn new<P: AsRef<Path>>(file_path: P) -> Result<Self, String> {
-        let file_path = file_path.as_ref().to_path_buf();
-        
-        if !file_path.exists() {
-            return Err("File does not exist".to_string());
+    // Serve several files from one page, so a batch needs only one QR scan
+    pub fn with_files<P: AsRef<Path>>(file_paths: &[P]) -> Result<Self, String> {
+        if file_paths.is_empty() {
+            return Err("No files to share".to_string());
         }
         
-        let filename = file_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .ok_or_else(|| "Invalid filename".to_string())?
-            .to_string();
+        let mut files = Vec::new();
+        for file_path in file_paths {
+            let path = file_path.as_ref().to_path_buf();
+            
+            if !path.exists() {
+                return Err(format!("File does not exist: {}", path.display()));
+            }
+            
+            let filename = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .ok_or_else(|| "Invalid filename".to_string())?
+                .to_string();
+            
+            files.push(SharedFile { path, filename });
+        }
         
         // Find available port
         let port = Self::find_available_port()?;
         
         Ok(Self {
-            file_path,
-            filename,
+            files,
             port,
             running: Arc::new(Mutex::new(false)),
+            requests: Arc::new(Mutex::new(Vec::new())),
+            held: Arc::new(AtomicUsize::new(0)),
         })
     }
     
@@ -68,18 +185,24 @@ impl ShareServer {
         Ok(format!("http://{}:{}", ip, self.port))
     }
     
-    pub fn generate_qr_code(&self) -> Result<String, String> {
+    pub fn generate_qr_code(&self) -> Result<QrImage, String> {
         let url = self.get_url()?;
-        
-        let code = QrCode::new(url.as_bytes())
-            .map_err(|e| format!("Failed to generate QR code: {}", e))?;
-        
-        let qr_string = code.render::<unicode::Dense1x2>()
-            .dark_color(unicode::Dense1x2::Light)
-            .light_color(unicode::Dense1x2::Dark)
-            .build();
-        
-        Ok(qr_string)
+        render_qr_code(&url)
+    }
+    
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+    
+    // Take the download requests submitted from the phone since the last call
+    pub fn take_requests(&self) -> Vec<String> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+    
+    // Tell the server how many taken requests the app still holds, so the form is turned
+    // away once those and the ones waiting here reach MAX_PENDING_REQUESTS
+    pub fn set_held_requests(&self, count: usize) {
+        self.held.store(count, Ordering::Relaxed);
     }
     
     pub fn start(&self) -> Result<(), String> {
@@ -87,9 +210,10 @@ impl ShareServer {
         let server = Server::http(&addr)
             .map_err(|e| format!("Failed to start server: {}", e))?;
         
-        let file_path = self.file_path.clone();
-        let filename = self.filename.clone();
+        let files = self.files.clone();
         let running = self.running.clone();
+        let requests = self.requests.clone();
+        let held = self.held.clone();
         
         // Set running to true
         {
@@ -98,7 +222,7 @@ impl ShareServer {
         }
         
         std::thread::spawn(move || {
-            for request in server.incoming_requests() {
+            for mut request in server.incoming_requests() {
                 // Check if we should stop
                 {
                     let r = running.lock().unwrap();
@@ -107,9 +231,57 @@ impl ShareServer {
                     }
                 }
                 
-                let path = request.url();
+                let url = request.url().to_string();
+                let (path, query) = url.split_once('?').unwrap_or((url.as_str(), ""));
                 
-                if path == "/" {
+                if path == "/request" && *request.method() == Method::Post {
+                    // Queue a search or link typed on the phone for approval in the app
+                    let mut body = String::new();
+                    let _ = request.as_reader().take(MAX_REQUEST_BODY).read_to_string(&mut body);
+                    
+                    let sent = match parse_form_field(&body, "q") {
+                        Some(input) if !input.trim().is_empty() => {
+                            let mut requests = requests.lock().unwrap();
+                            if requests.len() + held.load(Ordering::Relaxed) >= MAX_PENDING_REQUESTS {
+                                "full"
+                            } else {
+                                requests.push(input.trim().to_string());
+                                "1"
+                            }
+                        }
+                        _ => "0",
+                    };
+                    
+                    let location = format!("/?sent={}", sent);
+                    let response = Response::empty(303)
+                        .with_header(Header::from_bytes(&b"Location"[..], location.as_bytes()).unwrap());
+                    
+                    let _ = request.respond(response);
+                    
+                } else if path == "/" {
+                    let notice = match parse_form_field(query, "sent").as_deref() {
+                        Some("1") => r#"<p class="notice">Request sent! Approve it in Nightingale to start the download.</p>"#,
+                        Some("full") => r#"<p class="notice error">Too many requests are waiting for approval. Try again later.</p>"#,
+                        Some(_) => r#"<p class="notice error">Please enter a YouTube link or search.</p>"#,
+                        None => "",
+                    };
+                    
+                    // One entry per file; /download/<index> serves it
+                    let file_list = files.iter()
+                        .enumerate()
+                        .map(|(index, file)| format!(
+                            r#"<div class="filename">{}</div>
+    <a href="/download/{}" class="download-btn">Download MP3</a>"#,
+                            html_escape(&file.filename), index
+                        ))
+                        .collect::<Vec<_>>()
+                        .join("\n    ");
+                    
+                    let page_title = match files.as_slice() {
+                        [file] => html_escape(&file.filename),
+                        _ => format!("{} files", files.len()),
+                    };
+                    
                     // Serve download page
                     let html = format!(
                         r#"<!DOCTYPE html>
@@ -135,6 +307,7 @@ impl ShareServer {
         .download-btn {{
             display: inline-block;
             padding: 15px 40px;
+            margin-bottom: 20px;
             background: #4a9eff;
             color: white;
             text-decoration: none;
@@ -159,17 +332,52 @@ impl ShareServer {
             margin-top: 30px;
             font-size: 14px;
         }}
+        .request-form {{
+            margin-top: 50px;
+            padding-top: 30px;
+            border-top: 1px solid #333;
+        }}
+        .request-form input {{
+            width: 100%;
+            box-sizing: border-box;
+            padding: 12px;
+            border-radius: 8px;
+            border: 1px solid #444;
+            background: #2a2a2a;
+            color: #ffffff;
+            font-size: 16px;
+        }}
+        .request-form button {{
+            padding: 12px 30px;
+            margin-top: 15px;
+            background: #2a9d4a;
+            color: white;
+            border: none;
+            border-radius: 8px;
+            font-size: 16px;
+        }}
+        .notice {{
+            color: #2a9d4a;
+        }}
+        .notice.error {{
+            color: #d9534f;
+        }}
     </style>
 </head>
 <body>
     <h1>🎵 Nightingale File Transfer</h1>
-    <div class="filename">{}</div>
-    <a href="/download" class="download-btn">Download MP3</a>
+    {}
     <p class="info">The file will download to your device's Downloads folder.</p>
     <p class="info">Note: This will not add the file to the Music app. Use VLC or Files app for playback.</p>
+    <form class="request-form" method="post" action="/request">
+        <h2>Request a Track</h2>
+        {}
+        <input type="text" name="q" placeholder="Paste a YouTube link or type a search" autocomplete="off">
+        <button type="submit">Send to Nightingale</button>
+    </form>
 </body>
 </html>"#,
-                        filename, filename
+                        page_title, file_list, notice
                     );
                     
                     let response = Response::from_string(html)
@@ -177,9 +385,10 @@ impl ShareServer {
                     
                     let _ = request.respond(response);
                     
-                } else if path == "/download" {
+                } else if let Some(file) = shared_file(&files, path) {
                     // Serve the file
-                    match std::fs::read(&file_path) {
+                    let filename = &file.filename;
+                    match std::fs::read(&file.path) {
                         Ok(file_data) => {
                             let content_type = Header::from_bytes(&b"Content-Type"[..], &b"audio/mpeg"[..]).unwrap();
                             let content_disposition = Header::from_bytes(
@@ -215,3 +424,36 @@ impl ShareServer {
         *r = false;
     }
 }
+
+// The file for a download path: /download/<index>, or plain /download for the first file
+fn shared_file<'a>(files: &'a [SharedFile], path: &str) -> Option<&'a SharedFile> {
+    if path == "/download" {
+        return files.first();
+    }
+    
+    path.strip_prefix("/download/")
+        .and_then(|index| index.parse::<usize>().ok())
+        .and_then(|index| files.get(index))
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Extract a field from an application/x-www-form-urlencoded body or query string
+pub fn parse_form_field(encoded: &str, name: &str) -> Option<String> {
+    encoded.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        if key == name {
+            urlencoding::decode(&value.replace('+', " "))
+                .ok()
+                .map(|v| v.into_owned())
+        } else {
+            None
+        }
+    })
+}
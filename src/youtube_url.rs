@@ -0,0 +1,264 @@
+// What a search box entry or pasted link points at
+#[derive(Debug, Clone, PartialEq)]
+pub enum YouTubeLink {
+    Video {
+        id: String,
+        start: Option<u64>, // seconds, from t= or start=
+    },
+    Playlist {
+        list: String,
+    },
+    VideoInPlaylist {
+        id: String,
+        list: String,
+        start: Option<u64>,
+    },
+    Channel {
+        url: String, // on www.youtube.com, including any tab like /videos
+    },
+    Search(String),
+}
+
+impl YouTubeLink {
+    // Links on any YouTube host, and anything else as search terms; a bare video id can't be
+    // told apart from an 11-letter search like "Coldplay123", so it's left to the search
+    pub fn parse(input: &str) -> Self {
+        let input = input.trim();
+        Self::parse_url(input).unwrap_or_else(|| YouTubeLink::Search(input.to_string()))
+    }
+    
+    // Only links, e.g. youtube.com/watch, youtu.be, music., m., shorts and embeds;
+    // None for anything that isn't a YouTube link
+    pub fn parse_url(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if input.is_empty() || input.contains(char::is_whitespace) {
+            return None;
+        }
+        
+        let rest = input.strip_prefix("https://")
+            .or_else(|| input.strip_prefix("http://"))
+            .unwrap_or(input);
+        let (rest, fragment) = rest.split_once('#').unwrap_or((rest, ""));
+        let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let path = path.trim_end_matches('/');
+        
+        let host = host.to_ascii_lowercase();
+        let host = ["www.", "m.", "music."].iter()
+            .find_map(|prefix| host.strip_prefix(prefix))
+            .unwrap_or(host.as_str());
+        
+        // Timestamps also turn up in the fragment, as in #t=1m30s
+        let param = |name: &str| -> Option<String> {
+            query.split('&').chain(fragment.split('&')).find_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                (key == name)
+                    .then(|| urlencoding::decode(&value.replace('+', " ")).ok().map(|v| v.into_owned()))
+                    .flatten()
+            })
+        };
+        
+        let id = match host {
+            "youtu.be" => path.split('/').next().map(str::to_string),
+            "youtube.com" | "youtube-nocookie.com" => {
+                let mut segments = path.split('/');
+                match segments.next().unwrap_or("") {
+                    "watch" => param("v"),
+                    "shorts" | "embed" | "live" | "v" | "e" => segments.next().map(str::to_string),
+                    "playlist" => None,
+                    "results" => return param("search_query").map(YouTubeLink::Search),
+                    "channel" | "c" | "user" => return Some(YouTubeLink::Channel {
+                        url: format!("https://www.youtube.com/{}", path),
+                    }),
+                    handle if handle.starts_with('@') => return Some(YouTubeLink::Channel {
+                        url: format!("https://www.youtube.com/{}", path),
+                    }),
+                    // YouTube Music artist pages
+                    "browse" => return segments.next()
+                        .filter(|id| id.starts_with("UC"))
+                        .map(|id| YouTubeLink::Channel {
+                            url: format!("https://www.youtube.com/channel/{}", id),
+                        }),
+                    _ => None,
+                }
+            }
+            _ => return None,
+        };
+        
+        let id = id.filter(|id| is_video_id(id));
+        let list = param("list").filter(|list| !list.is_empty() && list.chars().all(is_id_char));
+        let start = param("t").or_else(|| param("start")).and_then(|t| parse_timestamp(&t));
+        
+        match (id, list) {
+            (Some(id), Some(list)) => Some(YouTubeLink::VideoInPlaylist { id, list, start }),
+            (Some(id), None) => Some(YouTubeLink::Video { id, start }),
+            (None, Some(list)) => Some(YouTubeLink::Playlist { list }),
+            (None, None) => None,
+        }
+    }
+    
+    // Canonical URL to hand to yt-dlp; a video in a playlist stands for just the video
+    pub fn url(&self) -> String {
+        match self {
+            YouTubeLink::Video { id, .. } | YouTubeLink::VideoInPlaylist { id, .. } => video_url(id),
+            YouTubeLink::Playlist { list } => playlist_url(list),
+            YouTubeLink::Channel { url } => url.clone(),
+            YouTubeLink::Search(terms) => format!(
+                "https://www.youtube.com/results?search_query={}",
+                urlencoding::encode(terms)
+            ),
+        }
+    }
+    
+    // The video and where the link starts playing it, if it has a timestamp
+    pub fn start(&self) -> Option<(&str, u64)> {
+        match self {
+            YouTubeLink::Video { id, start: Some(start) }
+            | YouTubeLink::VideoInPlaylist { id, start: Some(start), .. } => Some((id, *start)),
+            _ => None,
+        }
+    }
+}
+
+pub fn video_url(id: &str) -> String {
+    format!("https://www.youtube.com/watch?v={}", id)
+}
+
+pub fn playlist_url(list: &str) -> String {
+    format!("https://www.youtube.com/playlist?list={}", list)
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+pub fn is_video_id(id: &str) -> bool {
+    id.len() == 11 && id.chars().all(is_id_char)
+}
+
+// "90", "90s", "1m30s" or "1h2m3s"
+fn parse_timestamp(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(seconds);
+    }
+    
+    let mut total: u64 = 0;
+    let mut number = String::new();
+    for c in value.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'h' | 'm' | 's' => {
+                let unit = match c {
+                    'h' => 3600,
+                    'm' => 60,
+                    _ => 1,
+                };
+                total = total.checked_add(number.parse::<u64>().ok()?.checked_mul(unit)?)?;
+                number.clear();
+            }
+            _ => return None,
+        }
+    }
+    
+    number.is_empty().then_some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn video(id: &str, start: Option<u64>) -> YouTubeLink {
+        YouTubeLink::Video { id: id.to_string(), start }
+    }
+    
+    fn channel(url: &str) -> YouTubeLink {
+        YouTubeLink::Channel { url: url.to_string() }
+    }
+    
+    #[test]
+    fn parses_video_links_on_every_host() {
+        let id = "dQw4w9WgXcQ";
+        assert_eq!(YouTubeLink::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ"), video(id, None));
+        assert_eq!(YouTubeLink::parse("http://youtube.com/watch?feature=share&v=dQw4w9WgXcQ"), video(id, None));
+        assert_eq!(YouTubeLink::parse("https://music.youtube.com/watch?v=dQw4w9WgXcQ&si=abc"), video(id, None));
+        assert_eq!(YouTubeLink::parse("https://m.youtube.com/watch?v=dQw4w9WgXcQ"), video(id, None));
+        assert_eq!(YouTubeLink::parse("https://youtube.com/shorts/dQw4w9WgXcQ?feature=share"), video(id, None));
+        assert_eq!(YouTubeLink::parse("https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ?start=42"), video(id, Some(42)));
+        assert_eq!(YouTubeLink::parse("https://youtu.be/dQw4w9WgXcQ?si=7nVtX1a2b3"), video(id, None));
+        assert_eq!(YouTubeLink::parse("youtu.be/dQw4w9WgXcQ"), video(id, None));
+        assert_eq!(YouTubeLink::parse("https://www.youtube.com/live/dQw4w9WgXcQ?si=x"), video(id, None));
+    }
+    
+    #[test]
+    fn parses_timestamps() {
+        let id = "dQw4w9WgXcQ";
+        assert_eq!(YouTubeLink::parse("https://youtu.be/dQw4w9WgXcQ?t=90"), video(id, Some(90)));
+        assert_eq!(YouTubeLink::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=1m30s"), video(id, Some(90)));
+        assert_eq!(YouTubeLink::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ#t=1h2m3s"), video(id, Some(3723)));
+        assert_eq!(YouTubeLink::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=soon"), video(id, None));
+        assert_eq!(YouTubeLink::parse("https://youtu.be/dQw4w9WgXcQ?t=1m30s").start(), Some((id, 90)));
+        
+        assert_eq!(parse_timestamp("99999999999999999999h"), None);
+        assert_eq!(parse_timestamp("5124095576030432h"), None);
+        assert_eq!(parse_timestamp("18446744073709551615s1s"), None);
+        assert_eq!(parse_timestamp("1m30"), None);
+    }
+    
+    #[test]
+    fn parses_playlists() {
+        assert_eq!(
+            YouTubeLink::parse("https://www.youtube.com/playlist?list=PLx0sYbCqOb8TBPRdmBHs5Iftvv9TPboYG"),
+            YouTubeLink::Playlist { list: "PLx0sYbCqOb8TBPRdmBHs5Iftvv9TPboYG".to_string() }
+        );
+        assert_eq!(
+            YouTubeLink::parse("https://music.youtube.com/playlist?list=OLAK5uy_k0&si=abc"),
+            YouTubeLink::Playlist { list: "OLAK5uy_k0".to_string() }
+        );
+        
+        let link = YouTubeLink::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PLx0sYbCqOb8&index=3&t=10");
+        assert_eq!(link, YouTubeLink::VideoInPlaylist {
+            id: "dQw4w9WgXcQ".to_string(),
+            list: "PLx0sYbCqOb8".to_string(),
+            start: Some(10),
+        });
+        assert_eq!(link.url(), "https://www.youtube.com/watch?v=dQw4w9WgXcQ");
+    }
+    
+    #[test]
+    fn parses_channels() {
+        assert_eq!(YouTubeLink::parse("https://www.youtube.com/@LofiGirl"), channel("https://www.youtube.com/@LofiGirl"));
+        assert_eq!(YouTubeLink::parse("youtube.com/@LofiGirl/videos/"), channel("https://www.youtube.com/@LofiGirl/videos"));
+        assert_eq!(
+            YouTubeLink::parse("https://www.youtube.com/channel/UCSJ4gkVC6NrvII8umztf0Ow"),
+            channel("https://www.youtube.com/channel/UCSJ4gkVC6NrvII8umztf0Ow")
+        );
+        assert_eq!(YouTubeLink::parse("https://m.youtube.com/c/Vevo"), channel("https://www.youtube.com/c/Vevo"));
+        assert_eq!(YouTubeLink::parse("https://www.youtube.com/user/Vevo"), channel("https://www.youtube.com/user/Vevo"));
+        assert_eq!(
+            YouTubeLink::parse("https://music.youtube.com/browse/UCSJ4gkVC6NrvII8umztf0Ow"),
+            channel("https://www.youtube.com/channel/UCSJ4gkVC6NrvII8umztf0Ow")
+        );
+    }
+    
+    #[test]
+    fn treats_everything_else_as_search_terms() {
+        let search = |terms: &str| YouTubeLink::Search(terms.to_string());
+        assert_eq!(
+            YouTubeLink::parse("https://www.youtube.com/results?search_query=lofi+hip+hop%21"),
+            search("lofi hip hop!")
+        );
+        // Bare ids are looked up alongside the search instead
+        assert_eq!(YouTubeLink::parse("dQw4w9WgXcQ"), search("dQw4w9WgXcQ"));
+        assert_eq!(YouTubeLink::parse("Coldplay123"), search("Coldplay123"));
+        assert_eq!(YouTubeLink::parse("spider-man2"), search("spider-man2"));
+        assert_eq!(YouTubeLink::parse("AnnenMayKan"), search("AnnenMayKan"));
+        assert_eq!(YouTubeLink::parse(" daft punk "), search("daft punk"));
+        assert_eq!(YouTubeLink::parse("https://vimeo.com/12345"), search("https://vimeo.com/12345"));
+        assert_eq!(YouTubeLink::parse("https://www.youtube.com/watch?v=short"), search("https://www.youtube.com/watch?v=short"));
+        
+        assert!(is_video_id("dQw4w9WgXcQ"));
+        assert!(!is_video_id("dQw4w9WgXc"));
+        assert!(!is_video_id("dQw4w9WgXc!"));
+    }
+}
@@ -0,0 +1,248 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+// Release channel to pull yt-dlp builds from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Nightly,
+    Master,
+}
+
+impl UpdateChannel {
+    pub const ALL: [UpdateChannel; 3] = [
+        UpdateChannel::Stable,
+        UpdateChannel::Nightly,
+        UpdateChannel::Master,
+    ];
+    
+    fn repository(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "yt-dlp/yt-dlp",
+            UpdateChannel::Nightly => "yt-dlp/yt-dlp-nightly-builds",
+            UpdateChannel::Master => "yt-dlp/yt-dlp-master-builds",
+        }
+    }
+}
+
+impl std::fmt::Display for UpdateChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            UpdateChannel::Stable => "Stable",
+            UpdateChannel::Nightly => "Nightly",
+            UpdateChannel::Master => "Master",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// A published yt-dlp release with the binary for this platform
+#[derive(Debug, Clone)]
+pub struct Release {
+    pub version: String,
+    asset_name: String,
+    asset_url: String,
+    checksums_url: String,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+// Name of the release asset for the current platform
+fn platform_asset_name() -> Result<&'static str, String> {
+    if cfg!(target_os = "macos") {
+        Ok("yt-dlp_macos")
+    } else if cfg!(target_os = "linux") {
+        Ok("yt-dlp")
+    } else {
+        Err("Unsupported platform".to_string())
+    }
+}
+
+// Where the binary replaced by the last update is kept for rollback
+pub fn previous_binary_path(path: &Path) -> PathBuf {
+    path.with_file_name("yt-dlp.previous")
+}
+
+// Ask a yt-dlp binary for its version; the settings pass the managed one, since a copy
+// on PATH or from Homebrew isn't what updates and rollbacks replace
+pub async fn installed_version(binary: impl AsRef<std::ffi::OsStr>) -> Option<String> {
+    let output = tokio::process::Command::new(binary.as_ref())
+        .arg("--version")
+        .output()
+        .await
+        .ok()?;
+    
+    if !output.status.success() {
+        return None;
+    }
+    
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if version.is_empty() {
+        None
+    } else {
+        Some(version)
+    }
+}
+
+// Look up the newest release on a channel
+pub async fn fetch_latest_release(channel: UpdateChannel) -> Result<Release, String> {
+    let asset_name = platform_asset_name()?;
+    
+    // GitHub's API rejects requests without a user agent
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("nightingale/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+    
+    let url = format!("https://api.github.com/repos/{}/releases/latest", channel.repository());
+    let release: GithubRelease = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Failed to check for updates: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to read release info: {}", e))?;
+    
+    let find_asset = |name: &str| {
+        release.assets.iter()
+            .find(|asset| asset.name == name)
+            .map(|asset| asset.browser_download_url.clone())
+    };
+    
+    let asset_url = find_asset(asset_name)
+        .ok_or_else(|| format!("Release {} has no {} download", release.tag_name, asset_name))?;
+    let checksums_url = find_asset("SHA2-256SUMS")
+        .ok_or_else(|| format!("Release {} has no published checksums", release.tag_name))?;
+    
+    Ok(Release {
+        version: release.tag_name,
+        asset_name: asset_name.to_string(),
+        asset_url,
+        checksums_url,
+    })
+}
+
+// Download a release, verify it against the published SHA-256 sums and install it,
+// keeping the binary it replaces for rollback
+pub async fn install_release(release: &Release, path: &Path) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    
+    let client = reqwest::Client::new();
+    
+    let checksums = client
+        .get(&release.checksums_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download checksums: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Failed to download checksums: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read checksums: {}", e))?;
+    
+    let expected = expected_sha256(&checksums, &release.asset_name)
+        .ok_or_else(|| format!("No checksum published for {}", release.asset_name))?;
+    
+    let bytes = client
+        .get(&release.asset_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download yt-dlp: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Failed to download yt-dlp: {}", e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read download: {}", e))?;
+    
+    let actual = sha256_hex(&bytes);
+    
+    if actual != expected {
+        return Err(format!(
+            "Checksum mismatch for {} (expected {}, got {})",
+            release.asset_name, expected, actual
+        ));
+    }
+    
+    // Write next to the target first so a failed write never leaves a broken binary
+    let staged_path = path.with_file_name("yt-dlp.download");
+    std::fs::write(&staged_path, &bytes)
+        .map_err(|e| format!("Failed to write yt-dlp binary: {}", e))?;
+    
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged_path, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to set executable permissions: {}", e))?;
+    }
+    
+    if path.exists() {
+        std::fs::rename(path, previous_binary_path(path))
+            .map_err(|e| format!("Failed to keep previous yt-dlp: {}", e))?;
+    }
+    
+    std::fs::rename(&staged_path, path)
+        .map_err(|e| format!("Failed to install yt-dlp: {}", e))?;
+    
+    Ok(())
+}
+
+// The sum for file_name from a "<sha256>  <file name>" list, or a file holding just the sum
+pub fn expected_sha256(checksums: &str, file_name: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let matches = parts.next().is_none_or(|name| name.trim_start_matches('*') == file_name);
+        (matches && hash.len() == 64).then(|| hash.to_lowercase())
+    })
+}
+
+// Lowercase hex SHA-256 of a download, as checksum files publish it
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// Swap the current binary with the one kept from the last update
+pub fn rollback(path: &Path) -> Result<(), String> {
+    let previous = previous_binary_path(path);
+    if !previous.exists() {
+        return Err("No previous yt-dlp version to roll back to".to_string());
+    }
+    
+    let swap = path.with_file_name("yt-dlp.swap");
+    if path.exists() {
+        std::fs::rename(path, &swap)
+            .map_err(|e| format!("Failed to move current yt-dlp: {}", e))?;
+    }
+    
+    std::fs::rename(&previous, path)
+        .map_err(|e| format!("Failed to restore previous yt-dlp: {}", e))?;
+    
+    if swap.exists() {
+        std::fs::rename(&swap, &previous)
+            .map_err(|e| format!("Failed to keep replaced yt-dlp: {}", e))?;
+    }
+    
+    Ok(())
+}